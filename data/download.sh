@@ -1,5 +1,8 @@
 #!/bin/sh
 
-for challenge in 4 6 7 8 10; do
+for challenge in 4 6 7 8 10 20; do
   wget -nc https://cryptopals.com/static/challenge-data/$challenge.txt
 done
+
+# compare with the checksums in the `pals::data::FIXTURES`
+cksum 4.txt 6.txt 7.txt 8.txt 10.txt 20.txt
@@ -0,0 +1,1505 @@
+use std::{
+    convert::TryInto,
+    io::{self, Read, Write},
+};
+
+use aes::{Aes128, Aes192, Aes256, BlockCipher, NewBlockCipher};
+
+use super::{BytesCryptoExt, Error, Result, StreamCipher};
+
+pub const BLOCK_SIZE: usize = 16;
+
+/// The way to fill the last incomplete block of the plaintext
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    Pkcs7,
+    /// The data is expected to be already aligned to the block size
+    NoPadding,
+}
+
+impl Padding {
+    fn pad(self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut data = data.to_vec();
+        if self == Self::Pkcs7 {
+            #[allow(clippy::cast_possible_truncation)]
+            data.pad_pkcs7(BLOCK_SIZE as u8);
+        }
+
+        ensure_whole_blocks(&data)?;
+        Ok(data)
+    }
+
+    /// Pad the last block having `len` bytes of the data of the total `size`.
+    /// Returns the number of bytes to encrypt.
+    fn pad_block(self, block: &mut [u8; BLOCK_SIZE], len: usize, size: usize) -> Result<usize> {
+        match self {
+            Self::Pkcs7 => {
+                #[allow(clippy::cast_possible_truncation)]
+                let pad_byte = (BLOCK_SIZE - len) as u8;
+                for byte in &mut block[len..] {
+                    *byte = pad_byte;
+                }
+                Ok(BLOCK_SIZE)
+            }
+            Self::NoPadding if len == 0 => Ok(0),
+            Self::NoPadding => Err(Error::PartialBlock { size }),
+        }
+    }
+
+    /// The number of the padding bytes at the end of the decrypted data
+    fn padding_size(self, data: &[u8]) -> Result<usize> {
+        match self {
+            #[allow(clippy::cast_possible_truncation)]
+            Self::Pkcs7 => super::pkcs7_padding_size(data, BLOCK_SIZE as u8),
+            Self::NoPadding => Ok(0),
+        }
+    }
+
+    fn unpad(self, data: &mut Vec<u8>) -> Result<()> {
+        let size = self.padding_size(data)?;
+        data.truncate(data.len() - size);
+        Ok(())
+    }
+}
+
+const fn ensure_whole_blocks(data: &[u8]) -> Result<()> {
+    if data.len() % BLOCK_SIZE == 0 {
+        Ok(())
+    } else {
+        Err(Error::PartialBlock { size: data.len() })
+    }
+}
+
+/// The way to apply the block cipher to the data of arbitrary length
+pub trait BlockMode {
+    /// # Errors
+    /// The data is not aligned to the block size when the padding is disabled
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// # Errors
+    /// - the data is not aligned to the block size (for the block-oriented modes)
+    /// - the padding is invalid after decryption
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// The state carried from one piece of the data to the next one
+#[derive(Debug, Clone)]
+pub struct Chain {
+    /// The previous ciphertext block, the keystream or the shift register,
+    /// depending on the mode
+    block: [u8; BLOCK_SIZE],
+    /// The number of the blocks processed so far
+    counter: u64,
+}
+
+impl Chain {
+    const fn new(iv: [u8; BLOCK_SIZE]) -> Self {
+        Self {
+            block: iv,
+            counter: 0,
+        }
+    }
+}
+
+/// The mode processing the data piece by piece in place,
+/// keeping only a single block of the [`Chain`] between the pieces.
+///
+/// Every piece except the last one should consist of the whole blocks.
+/// See the [`Encryptor`] and the [`Decryptor`] to process the data of any size
+/// with the constant memory.
+pub trait StreamingMode {
+    /// The padding of the last block.
+    /// None for the modes producing the ciphertext of the same size as the plaintext.
+    fn padding(&self) -> Option<Padding>;
+
+    fn start(&self) -> Chain;
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]);
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]);
+
+    /// Encrypt the data without any copying.
+    /// The padding is never added, so the data of the block-oriented modes
+    /// should be already aligned to the block size.
+    ///
+    /// # Errors
+    /// The data is not aligned to the block size (for the block-oriented modes)
+    fn encrypt_in_place(&self, data: &mut [u8]) -> Result<()> {
+        if self.padding().is_some() {
+            ensure_whole_blocks(data)?;
+        }
+        self.encrypt_next(&mut self.start(), data);
+        Ok(())
+    }
+
+    /// Decrypt the data without any copying.
+    /// The padding is never removed, see the [`StreamingMode::encrypt_in_place`].
+    ///
+    /// # Errors
+    /// The data is not aligned to the block size (for the block-oriented modes)
+    fn decrypt_in_place(&self, data: &mut [u8]) -> Result<()> {
+        if self.padding().is_some() {
+            ensure_whole_blocks(data)?;
+        }
+        self.decrypt_next(&mut self.start(), data);
+        Ok(())
+    }
+}
+
+impl<M> StreamingMode for &M
+where
+    M: StreamingMode + ?Sized,
+{
+    fn padding(&self) -> Option<Padding> {
+        (**self).padding()
+    }
+
+    fn start(&self) -> Chain {
+        (**self).start()
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        (**self).encrypt_next(chain, data);
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        (**self).decrypt_next(chain, data);
+    }
+}
+
+impl<M> BlockMode for M
+where
+    M: StreamingMode,
+{
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut data = match self.padding() {
+            Some(padding) => padding.pad(data)?,
+            None => data.to_vec(),
+        };
+        self.encrypt_next(&mut self.start(), &mut data);
+        Ok(data)
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut data = data.to_vec();
+        self.decrypt_in_place(&mut data)?;
+        if let Some(padding) = self.padding() {
+            padding.unpad(&mut data)?;
+        }
+        Ok(data)
+    }
+}
+
+/// Single block encryption primitive every mode is built upon.
+/// The AES variant is chosen by the length of the key.
+#[derive(Clone)]
+enum Cipher {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl Cipher {
+    fn new(key: &[u8]) -> Result<Self> {
+        let invalid = |_| Error::BadKeyLength(key.len());
+        match key.len() {
+            16 => Aes128::new_varkey(key).map(Self::Aes128).map_err(invalid),
+            24 => Aes192::new_varkey(key).map(Self::Aes192).map_err(invalid),
+            32 => Aes256::new_varkey(key).map(Self::Aes256).map_err(invalid),
+            len => Err(Error::BadKeyLength(len)),
+        }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; BLOCK_SIZE] = block.try_into().expect("Only full blocks allowed");
+        match self {
+            Self::Aes128(cipher) => cipher.encrypt_block(block.into()),
+            Self::Aes192(cipher) => cipher.encrypt_block(block.into()),
+            Self::Aes256(cipher) => cipher.encrypt_block(block.into()),
+        }
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let block: &mut [u8; BLOCK_SIZE] = block.try_into().expect("Only full blocks allowed");
+        match self {
+            Self::Aes128(cipher) => cipher.decrypt_block(block.into()),
+            Self::Aes192(cipher) => cipher.decrypt_block(block.into()),
+            Self::Aes256(cipher) => cipher.decrypt_block(block.into()),
+        }
+    }
+}
+
+fn xor_in_place(block: &mut [u8], other: &[u8]) {
+    for (x, y) in block.iter_mut().zip(other) {
+        *x ^= y;
+    }
+}
+
+/// The copy of the (possibly partial) block
+fn copy_block(block: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut copy = [0; BLOCK_SIZE];
+    copy[..block.len()].copy_from_slice(block);
+    copy
+}
+
+/// Collects the parameters shared by all the modes
+///
+/// ```
+/// use pals::aes_cypher::{BlockMode, Builder};
+///
+/// let cbc = Builder::new(b"YELLOW SUBMARINE")?.iv(&[1; 16])?.cbc();
+/// let enc = cbc.encrypt(b"attack at dawn")?;
+/// assert_eq!(cbc.decrypt(&enc)?, b"attack at dawn");
+/// # Ok::<(), pals::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct Builder {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+    padding: Padding,
+}
+
+impl Builder {
+    /// The key of 16, 24 or 32 bytes selects the AES-128, AES-192 or AES-256 respectively.
+    /// The IV is all zeroes and the PKCS#7 padding is used by default.
+    ///
+    /// # Errors
+    /// The key has unsupported length
+    pub fn new(key: &[u8]) -> Result<Self> {
+        Ok(Self {
+            cipher: Cipher::new(key)?,
+            iv: [0; BLOCK_SIZE],
+            padding: Padding::Pkcs7,
+        })
+    }
+
+    /// # Errors
+    /// The IV is not of the block size
+    pub fn iv(mut self, iv: &[u8]) -> Result<Self> {
+        self.iv = iv.try_into().map_err(|_| Error::BadIvLength(iv.len()))?;
+        Ok(self)
+    }
+
+    #[must_use]
+    pub const fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn ecb(&self) -> Ecb {
+        Ecb {
+            cipher: self.cipher.clone(),
+            padding: self.padding,
+        }
+    }
+
+    pub fn cbc(&self) -> Cbc {
+        Cbc {
+            cipher: self.cipher.clone(),
+            iv: self.iv,
+            padding: self.padding,
+        }
+    }
+
+    pub fn pcbc(&self) -> Pcbc {
+        Pcbc {
+            cipher: self.cipher.clone(),
+            iv: self.iv,
+            padding: self.padding,
+        }
+    }
+
+    /// The padding is not used in the CFB mode
+    pub fn cfb8(&self) -> Cfb8 {
+        Cfb8 {
+            cipher: self.cipher.clone(),
+            iv: self.iv,
+        }
+    }
+
+    /// The padding is not used in the CFB mode
+    pub fn cfb128(&self) -> Cfb128 {
+        Cfb128 {
+            cipher: self.cipher.clone(),
+            iv: self.iv,
+        }
+    }
+
+    /// The padding is not used in the OFB mode
+    pub fn ofb(&self) -> Ofb {
+        Ofb {
+            cipher: self.cipher.clone(),
+            iv: self.iv,
+        }
+    }
+
+    /// The IV and padding are not used in the CTR mode,
+    /// the nonce is a part of the counter layout instead
+    pub fn ctr(&self, layout: CounterLayout) -> Ctr {
+        Ctr {
+            cipher: self.cipher.clone(),
+            layout,
+        }
+    }
+}
+
+/// Electronic codebook: every block is encrypted independently
+pub struct Ecb {
+    cipher: Cipher,
+    padding: Padding,
+}
+
+impl StreamingMode for Ecb {
+    fn padding(&self) -> Option<Padding> {
+        Some(self.padding)
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new([0; BLOCK_SIZE])
+    }
+
+    fn encrypt_next(&self, _chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            self.cipher.encrypt_block(block);
+        }
+    }
+
+    fn decrypt_next(&self, _chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            self.cipher.decrypt_block(block);
+        }
+    }
+}
+
+/// Cipher block chaining: every plaintext block is mixed
+/// with the previous ciphertext block (or the IV) before encryption
+pub struct Cbc {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+    padding: Padding,
+}
+
+impl StreamingMode for Cbc {
+    fn padding(&self) -> Option<Padding> {
+        Some(self.padding)
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new(self.iv)
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            // mix with the previous block
+            xor_in_place(block, &chain.block);
+            self.cipher.encrypt_block(block);
+            chain.block.copy_from_slice(block);
+        }
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            let cipher_block = copy_block(block);
+            self.cipher.decrypt_block(block);
+            // mix with the previous block
+            xor_in_place(block, &chain.block);
+            chain.block = cipher_block;
+        }
+    }
+}
+
+/// Propagating cipher block chaining.
+///
+/// Every plaintext block is mixed with both the previous plaintext
+/// and the previous ciphertext blocks,
+/// so a single changed ciphertext byte garbles all the following blocks
+pub struct Pcbc {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+    padding: Padding,
+}
+
+impl StreamingMode for Pcbc {
+    fn padding(&self) -> Option<Padding> {
+        Some(self.padding)
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new(self.iv)
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            let plain_block = copy_block(block);
+            xor_in_place(block, &chain.block);
+            self.cipher.encrypt_block(block);
+
+            chain.block = plain_block;
+            xor_in_place(&mut chain.block, block);
+        }
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            let cipher_block = copy_block(block);
+            self.cipher.decrypt_block(block);
+            xor_in_place(block, &chain.block);
+
+            chain.block = cipher_block;
+            xor_in_place(&mut chain.block, block);
+        }
+    }
+}
+
+/// 8-bit cipher feedback: every byte is mixed with the first byte
+/// of the encrypted shift register holding the previous ciphertext bytes
+pub struct Cfb8 {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+}
+
+impl Cfb8 {
+    fn process(&self, register: &mut [u8; BLOCK_SIZE], data: &mut [u8], encrypt: bool) {
+        for byte in data {
+            let mut block = *register;
+            self.cipher.encrypt_block(&mut block);
+
+            let cipher_byte = if encrypt { *byte ^ block[0] } else { *byte };
+            *byte ^= block[0];
+            register.copy_within(1.., 0);
+            register[BLOCK_SIZE - 1] = cipher_byte;
+        }
+    }
+}
+
+impl StreamingMode for Cfb8 {
+    fn padding(&self) -> Option<Padding> {
+        None
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new(self.iv)
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        self.process(&mut chain.block, data, true);
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        self.process(&mut chain.block, data, false);
+    }
+}
+
+/// 128-bit cipher feedback: the previous ciphertext block (or the IV)
+/// gets encrypted to produce the keystream for the current block
+pub struct Cfb128 {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+}
+
+impl StreamingMode for Cfb128 {
+    fn padding(&self) -> Option<Padding> {
+        None
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new(self.iv)
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        let keystream = &mut chain.block;
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            self.cipher.encrypt_block(keystream);
+            xor_in_place(block, keystream);
+            keystream[..block.len()].copy_from_slice(block);
+        }
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        let keystream = &mut chain.block;
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            let cipher_block = copy_block(block);
+            self.cipher.encrypt_block(keystream);
+            xor_in_place(block, keystream);
+            keystream[..block.len()].copy_from_slice(&cipher_block[..block.len()]);
+        }
+    }
+}
+
+/// Output feedback: the IV gets encrypted over and over again
+/// to produce the keystream independent of the data
+pub struct Ofb {
+    cipher: Cipher,
+    iv: [u8; BLOCK_SIZE],
+}
+
+impl StreamingMode for Ofb {
+    fn padding(&self) -> Option<Padding> {
+        None
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new(self.iv)
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            self.cipher.encrypt_block(&mut chain.block);
+            xor_in_place(block, &chain.block);
+        }
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        self.encrypt_next(chain, data);
+    }
+}
+
+/// How the nonce and the block counter are placed into the counter block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterLayout {
+    /// 64-bit nonce followed by 64-bit block counter, both little-endian.
+    /// This one is used in the cryptopals challenges.
+    LittleEndian { nonce: u64 },
+    /// 64-bit nonce followed by 64-bit block counter, both big-endian
+    BigEndian { nonce: u64 },
+    /// 96-bit nonce followed by 32-bit big-endian block counter (as in RFC 3686 or GCM).
+    /// The counter wraps around after 2^32 blocks.
+    Nonce96 { nonce: [u8; 12] },
+}
+
+impl CounterLayout {
+    fn counter_block(self, counter: u64) -> [u8; BLOCK_SIZE] {
+        let mut block = [0; BLOCK_SIZE];
+        match self {
+            Self::LittleEndian { nonce } => {
+                block[..8].copy_from_slice(&nonce.to_le_bytes());
+                block[8..].copy_from_slice(&counter.to_le_bytes());
+            }
+            Self::BigEndian { nonce } => {
+                block[..8].copy_from_slice(&nonce.to_be_bytes());
+                block[8..].copy_from_slice(&counter.to_be_bytes());
+            }
+            Self::Nonce96 { nonce } => {
+                block[..12].copy_from_slice(&nonce);
+                #[allow(clippy::cast_possible_truncation)]
+                block[12..].copy_from_slice(&(counter as u32).to_be_bytes());
+            }
+        }
+        block
+    }
+}
+
+/// Counter mode: turns the block cipher into a stream cipher
+/// by encrypting the successive counter blocks
+///
+/// ```
+/// use pals::{aes_cypher::{Builder, CounterLayout}, StreamCipher};
+///
+/// let ctr = Builder::new(b"YELLOW SUBMARINE")?.ctr(CounterLayout::LittleEndian { nonce: 0 });
+/// let enc = b"attack at dawn".xor(ctr.keystream());
+/// assert_eq!(enc.xor(ctr.keystream()), b"attack at dawn");
+/// # Ok::<(), pals::Error>(())
+/// ```
+pub struct Ctr {
+    cipher: Cipher,
+    layout: CounterLayout,
+}
+
+impl Ctr {
+    fn keystream_block(&self, counter: u64) -> [u8; BLOCK_SIZE] {
+        let mut block = self.layout.counter_block(counter);
+        self.cipher.encrypt_block(&mut block);
+        block
+    }
+
+    pub fn keystream(&self) -> Keystream<'_> {
+        self.keystream_at(0)
+    }
+
+    /// The keystream starting from the given byte position
+    pub fn keystream_at(&self, position: u64) -> Keystream<'_> {
+        let block_size = BLOCK_SIZE as u64;
+        let counter = position / block_size;
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = (position % block_size) as usize;
+
+        Keystream {
+            ctr: self,
+            counter,
+            block: self.keystream_block(counter),
+            offset,
+        }
+    }
+
+    /// Encrypt (or decrypt) the piece of data located at the given byte position of the stream
+    pub fn apply_at(&self, data: &[u8], position: u64) -> Vec<u8> {
+        data.xor(self.keystream_at(position))
+    }
+}
+
+impl StreamingMode for Ctr {
+    fn padding(&self) -> Option<Padding> {
+        None
+    }
+
+    fn start(&self) -> Chain {
+        Chain::new([0; BLOCK_SIZE])
+    }
+
+    fn encrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        for block in data.chunks_mut(BLOCK_SIZE) {
+            xor_in_place(block, &self.keystream_block(chain.counter));
+            chain.counter = chain.counter.wrapping_add(1);
+        }
+    }
+
+    fn decrypt_next(&self, chain: &mut Chain, data: &mut [u8]) {
+        self.encrypt_next(chain, data);
+    }
+}
+
+/// Infinite sequence of the CTR mode keystream bytes
+#[derive(Clone)]
+pub struct Keystream<'a> {
+    ctr: &'a Ctr,
+    counter: u64,
+    block: [u8; BLOCK_SIZE],
+    offset: usize,
+}
+
+impl Keystream<'_> {
+    /// The byte position of the next generated byte.
+    /// Wraps around after the `u64::MAX` like the counter does.
+    pub const fn position(&self) -> u64 {
+        self.counter
+            .wrapping_mul(BLOCK_SIZE as u64)
+            .wrapping_add(self.offset as u64)
+    }
+
+    /// Move to the arbitrary byte position of the stream
+    pub fn seek(&mut self, position: u64) {
+        *self = self.ctr.keystream_at(position);
+    }
+}
+
+impl Iterator for Keystream<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.offset == BLOCK_SIZE {
+            self.counter = self.counter.wrapping_add(1);
+            self.block = self.ctr.keystream_block(self.counter);
+            self.offset = 0;
+        }
+
+        let byte = self.block[self.offset];
+        self.offset += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+fn invalid_data(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Encrypts everything written into it block by block
+/// and writes the ciphertext into the inner writer.
+///
+/// The last (padded) block is only written by the [`Encryptor::finish`],
+/// so the ciphertext is incomplete if the encryptor is just dropped.
+/// The ciphertext the inner writer has failed to take is written first
+/// by the next call, so the failed writes can be retried.
+///
+/// ```
+/// use std::io::Write;
+/// use pals::aes_cypher::{BlockMode, Builder, Encryptor};
+///
+/// let builder = Builder::new(b"YELLOW SUBMARINE")?.iv(&[1; 16])?;
+/// let mut encryptor = Encryptor::new(builder.cbc(), Vec::new());
+/// encryptor.write_all(b"attack ")?;
+/// encryptor.write_all(b"at dawn")?;
+/// let enc = encryptor.finish()?;
+/// assert_eq!(enc, builder.cbc().encrypt(b"attack at dawn")?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Encryptor<W, M> {
+    mode: M,
+    chain: Chain,
+    inner: W,
+    block: [u8; BLOCK_SIZE],
+    /// The number of the plaintext bytes in the current block
+    filled: usize,
+    /// The number of the plaintext bytes written so far
+    size: usize,
+    /// The encrypted block not taken by the inner writer yet
+    out: [u8; BLOCK_SIZE],
+    out_start: usize,
+    out_end: usize,
+}
+
+impl<W, M> Encryptor<W, M>
+where
+    W: Write,
+    M: StreamingMode,
+{
+    pub fn new(mode: M, inner: W) -> Self {
+        Self {
+            chain: mode.start(),
+            mode,
+            inner,
+            block: [0; BLOCK_SIZE],
+            filled: 0,
+            size: 0,
+            out: [0; BLOCK_SIZE],
+            out_start: 0,
+            out_end: 0,
+        }
+    }
+
+    /// Write the rest of the encrypted block into the inner writer
+    fn write_out(&mut self) -> io::Result<()> {
+        while self.out_start < self.out_end {
+            match self.inner.write(&self.out[self.out_start..self.out_end]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => self.out_start += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypt and write the last block and return the inner writer
+    ///
+    /// # Errors
+    /// - the inner writer fails
+    /// - the data is not aligned to the block size when the padding is disabled
+    ///   (the [`io::ErrorKind::InvalidData`] with the [`Error`] inside)
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_out()?;
+        let len = match self.mode.padding() {
+            Some(padding) => padding
+                .pad_block(&mut self.block, self.filled, self.size)
+                .map_err(invalid_data)?,
+            None => self.filled,
+        };
+
+        let last = &mut self.block[..len];
+        self.mode.encrypt_next(&mut self.chain, last);
+        self.inner.write_all(last)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W, M> Write for Encryptor<W, M>
+where
+    W: Write,
+    M: StreamingMode,
+{
+    /// Nothing is accepted if the rest of the previous block cannot be written.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_out()?;
+
+        let len = buf.len().min(BLOCK_SIZE - self.filled);
+        self.block[self.filled..self.filled + len].copy_from_slice(&buf[..len]);
+        self.filled += len;
+        self.size += len;
+
+        if self.filled == BLOCK_SIZE {
+            self.out = self.block;
+            self.mode.encrypt_next(&mut self.chain, &mut self.out);
+            self.out_start = 0;
+            self.out_end = BLOCK_SIZE;
+            self.filled = 0;
+            // the data is already accepted, so the failure is reported by the next call
+            let _ = self.write_out();
+        }
+        Ok(len)
+    }
+
+    /// Flush the inner writer. The incomplete block is kept
+    /// until more data is written or the [`Encryptor::finish`] is called.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_out()?;
+        self.inner.flush()
+    }
+}
+
+/// Decrypts the ciphertext from the inner reader block by block.
+///
+/// One block of the ciphertext is read ahead to find out
+/// which block is the last one and should be unpadded.
+///
+/// ```
+/// use std::io::Read;
+/// use pals::aes_cypher::{BlockMode, Builder, Decryptor};
+///
+/// let builder = Builder::new(b"YELLOW SUBMARINE")?.iv(&[1; 16])?;
+/// let enc = builder.cbc().encrypt(b"attack at dawn")?;
+///
+/// let mut plain = String::new();
+/// Decryptor::new(builder.cbc(), enc.as_slice()).read_to_string(&mut plain)?;
+/// assert_eq!(plain, "attack at dawn");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Decryptor<R, M> {
+    mode: M,
+    chain: Chain,
+    inner: R,
+    /// The next block of the ciphertext, None until the first read
+    ahead: Option<([u8; BLOCK_SIZE], usize)>,
+    /// The block being read from the inner reader, kept between the failed reads
+    pending: [u8; BLOCK_SIZE],
+    pending_len: usize,
+    /// The decrypted block being read out
+    block: [u8; BLOCK_SIZE],
+    start: usize,
+    end: usize,
+    /// The number of the ciphertext bytes read so far
+    size: usize,
+    done: bool,
+}
+
+impl<R, M> Decryptor<R, M>
+where
+    R: Read,
+    M: StreamingMode,
+{
+    pub fn new(mode: M, inner: R) -> Self {
+        Self {
+            chain: mode.start(),
+            mode,
+            inner,
+            ahead: None,
+            pending: [0; BLOCK_SIZE],
+            pending_len: 0,
+            block: [0; BLOCK_SIZE],
+            start: 0,
+            end: 0,
+            size: 0,
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read the whole block unless the reader is exhausted earlier
+    fn read_ahead(&mut self) -> io::Result<([u8; BLOCK_SIZE], usize)> {
+        while self.pending_len < BLOCK_SIZE {
+            match self.inner.read(&mut self.pending[self.pending_len..]) {
+                Ok(0) => break,
+                Ok(n) => {
+                    self.pending_len += n;
+                    self.size += n;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let len = self.pending_len;
+        if self.mode.padding().is_some() && len != 0 && len != BLOCK_SIZE {
+            return Err(invalid_data(Error::PartialBlock { size: self.size }));
+        }
+        self.pending_len = 0;
+        Ok((self.pending, len))
+    }
+
+    /// Decrypt the next block of the ciphertext.
+    /// Nothing changes if the inner reader fails.
+    fn fill(&mut self) -> io::Result<()> {
+        if self.ahead.is_none() {
+            self.ahead = Some(self.read_ahead()?);
+        }
+        let (mut block, len) = self.ahead.unwrap_or_default();
+        let next = self.read_ahead()?;
+        let last = next.1 == 0;
+        self.ahead = Some(next);
+
+        self.mode.decrypt_next(&mut self.chain, &mut block[..len]);
+        let len = match self.mode.padding() {
+            Some(padding) if last => {
+                len - padding.padding_size(&block[..len]).map_err(invalid_data)?
+            }
+            _ => len,
+        };
+
+        self.block = block;
+        self.start = 0;
+        self.end = len;
+        self.done = last;
+        Ok(())
+    }
+}
+
+impl<R, M> Read for Decryptor<R, M>
+where
+    R: Read,
+    M: StreamingMode,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.start == self.end && !self.done {
+            self.fill()?;
+        }
+
+        let len = buf.len().min(self.end - self.start);
+        buf[..len].copy_from_slice(&self.block[self.start..self.start + len]);
+        self.start += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::StrCryptoExt;
+
+    const KEY: &[u8] = b"YELLOW SUBMARINE";
+
+    #[test]
+    fn ecb_same_blocks_produce_same_ciphertext() {
+        let ecb = Builder::new(KEY).unwrap().ecb();
+        let enc = ecb.encrypt(&[0; 48]).unwrap();
+
+        // two blocks of data and one block of padding
+        assert_eq!(enc.len(), 64);
+        assert_eq!(enc[..16], enc[16..32]);
+        assert_eq!(enc[16..32], enc[32..48]);
+        assert_ne!(enc[32..48], enc[48..]);
+    }
+
+    #[test]
+    fn ecb_roundtrip() {
+        let ecb = Builder::new(KEY).unwrap().ecb();
+        let plain = b"Rollin' in my 5.0";
+        assert_eq!(ecb.decrypt(&ecb.encrypt(plain).unwrap()).unwrap(), plain);
+    }
+
+    #[test]
+    fn cbc_same_blocks_produce_different_ciphertext() {
+        let cbc = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap().cbc();
+        let enc = cbc.encrypt(&[0; 48]).unwrap();
+
+        assert_eq!(enc.len(), 64);
+        assert_ne!(enc[..16], enc[16..32]);
+        assert_ne!(enc[16..32], enc[32..48]);
+    }
+
+    #[test]
+    fn cbc_roundtrip() {
+        let cbc = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap().cbc();
+        let plain = b"With my rag-top down so my hair can blow";
+        assert_eq!(cbc.decrypt(&cbc.encrypt(plain).unwrap()).unwrap(), plain);
+    }
+
+    #[test]
+    fn cbc_with_zero_iv_starts_as_ecb() {
+        let builder = Builder::new(KEY).unwrap().padding(Padding::NoPadding);
+        let plain = [7; 32];
+
+        let ecb = builder.ecb().encrypt(&plain).unwrap();
+        let cbc = builder.cbc().encrypt(&plain).unwrap();
+        assert_eq!(ecb[..16], cbc[..16]);
+        assert_ne!(ecb[16..], cbc[16..]);
+    }
+
+    #[test]
+    fn no_padding_keeps_the_size() {
+        let ecb = Builder::new(KEY).unwrap().padding(Padding::NoPadding).ecb();
+        let enc = ecb.encrypt(&[1; 32]).unwrap();
+
+        assert_eq!(enc.len(), 32);
+        assert_eq!(ecb.decrypt(&enc).unwrap(), vec![1; 32]);
+    }
+
+    const NIST_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const NIST_IV: &str = "000102030405060708090a0b0c0d0e0f";
+    const NIST_PLAIN: &str = "6bc1bee22e409f96e93d7e117393172a\
+                              ae2d8a571e03ac9c9eb76fac45af8e51\
+                              30c81c46a35ce411e5fbc1191a0a52ef\
+                              f69f2445df4f9b17ad2b417be66c3710";
+
+    fn assert_nist_known_answer<M, F>(build: F, plain: &str, expected: &str)
+    where
+        M: BlockMode,
+        F: Fn(&Builder) -> M,
+    {
+        let key = NIST_KEY.parse_hex().unwrap();
+        let iv = NIST_IV.parse_hex().unwrap();
+        let builder = Builder::new(&key)
+            .unwrap()
+            .iv(&iv)
+            .unwrap()
+            .padding(Padding::NoPadding);
+        let mode = build(&builder);
+
+        let plain = plain.parse_hex().unwrap();
+        let expected = expected.parse_hex().unwrap();
+        assert_eq!(mode.encrypt(&plain).unwrap(), expected);
+        assert_eq!(mode.decrypt(&expected).unwrap(), plain);
+    }
+
+    #[test]
+    fn unsupported_key_length() {
+        for len in &[0, 1, 8, 15, 17, 20, 31, 33, 64] {
+            let key = vec![0; *len];
+            assert_eq!(Builder::new(&key).err(), Some(Error::BadKeyLength(*len)));
+        }
+    }
+
+    #[test]
+    fn unsupported_iv_length() {
+        let builder = Builder::new(KEY).unwrap();
+        assert_eq!(builder.iv(&[0; 8]).err(), Some(Error::BadIvLength(8)));
+    }
+
+    #[test]
+    fn partial_blocks_are_rejected() {
+        let builder = Builder::new(KEY).unwrap();
+        let ecb = builder.ecb();
+        assert_eq!(ecb.decrypt(&[0; 20]), Err(Error::PartialBlock { size: 20 }));
+
+        let ecb = builder.padding(Padding::NoPadding).ecb();
+        assert_eq!(ecb.encrypt(&[0; 20]), Err(Error::PartialBlock { size: 20 }));
+    }
+
+    #[test]
+    fn bad_padding_is_rejected() {
+        let builder = Builder::new(KEY).unwrap();
+        let enc = builder
+            .clone()
+            .padding(Padding::NoPadding)
+            .ecb()
+            .encrypt(&[0; 16])
+            .unwrap();
+        assert_eq!(builder.ecb().decrypt(&enc), Err(Error::InvalidPadding));
+    }
+
+    #[test]
+    fn cbc_aes192_nist_known_answer() {
+        // NIST SP 800-38A, F.2.3 CBC-AES192.Encrypt
+        let key = "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b"
+            .parse_hex()
+            .unwrap();
+        let cbc = Builder::new(&key)
+            .unwrap()
+            .iv(&NIST_IV.parse_hex().unwrap())
+            .unwrap()
+            .padding(Padding::NoPadding)
+            .cbc();
+
+        let expected = "4f021db243bc633d7178183a9fa071e8\
+                        b4d9ada9ad7dedf4e5e738763f69145a\
+                        571b242012fb7ae07fa9baac3df102e0\
+                        08b0e27988598881d920a9e64f5615cd"
+            .parse_hex()
+            .unwrap();
+        assert_eq!(
+            cbc.encrypt(&NIST_PLAIN.parse_hex().unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn cbc_aes256_nist_known_answer() {
+        // NIST SP 800-38A, F.2.5 CBC-AES256.Encrypt
+        let key = "603deb1015ca71be2b73aef0857d7781\
+                   1f352c073b6108d72d9810a30914dff4"
+            .parse_hex()
+            .unwrap();
+        let cbc = Builder::new(&key)
+            .unwrap()
+            .iv(&NIST_IV.parse_hex().unwrap())
+            .unwrap()
+            .padding(Padding::NoPadding)
+            .cbc();
+
+        let expected = "f58c4c04d6e5f1ba779eabfb5f7bfbd6\
+                        9cfc4e967edb808d679f777bc6702c7d\
+                        39f23369a9d9bacfa530e26304231461\
+                        b2eb05e2c39be9fcda6c19078c6a9d1b"
+            .parse_hex()
+            .unwrap();
+        assert_eq!(
+            cbc.encrypt(&NIST_PLAIN.parse_hex().unwrap()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn cbc_nist_known_answer() {
+        // NIST SP 800-38A, F.2.1 CBC-AES128.Encrypt
+        assert_nist_known_answer(
+            Builder::cbc,
+            NIST_PLAIN,
+            "7649abac8119b246cee98e9b12e9197d\
+             5086cb9b507219ee95db113a917678b2\
+             73bed6b8e3c1743b7116e69e22229516\
+             3ff1caa1681fac09120eca307586e1a7",
+        );
+    }
+
+    #[test]
+    fn cfb8_nist_known_answer() {
+        // NIST SP 800-38A, F.3.7 CFB8-AES128.Encrypt
+        assert_nist_known_answer(
+            Builder::cfb8,
+            &NIST_PLAIN[..36],
+            "3b79424c9c0dd436bace9e0ed4586a4f32b9",
+        );
+    }
+
+    #[test]
+    fn cfb128_nist_known_answer() {
+        // NIST SP 800-38A, F.3.13 CFB128-AES128.Encrypt
+        assert_nist_known_answer(
+            Builder::cfb128,
+            NIST_PLAIN,
+            "3b3fd92eb72dad20333449f8e83cfb4a\
+             c8a64537a0b3a93fcde3cdad9f1ce58b\
+             26751f67a3cbb140b1808cf187a4f4df\
+             c04b05357c5d1c0eeac4c66f9ff7f2e6",
+        );
+    }
+
+    #[test]
+    fn ofb_nist_known_answer() {
+        // NIST SP 800-38A, F.4.1 OFB-AES128.Encrypt
+        assert_nist_known_answer(
+            Builder::ofb,
+            NIST_PLAIN,
+            "3b3fd92eb72dad20333449f8e83cfb4a\
+             7789508d16918f03f53c52dac54ed825\
+             9740051e9c5fecf64344f7a82260edcc\
+             304c6528f659c77866a510d9c1d6ae5e",
+        );
+    }
+
+    #[test]
+    fn stream_modes_keep_partial_blocks() {
+        let builder = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap();
+        let plain = b"Cooking MC's like a pound of bacon";
+
+        let modes: Vec<Box<dyn BlockMode>> = vec![
+            Box::new(builder.cfb8()),
+            Box::new(builder.cfb128()),
+            Box::new(builder.ofb()),
+        ];
+        for mode in modes {
+            let enc = mode.encrypt(plain).unwrap();
+            assert_eq!(enc.len(), plain.len());
+            assert_eq!(mode.decrypt(&enc).unwrap(), plain);
+        }
+    }
+
+    #[test]
+    fn pcbc_roundtrip() {
+        let pcbc = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap().pcbc();
+        let plain = b"With my rag-top down so my hair can blow";
+        assert_eq!(pcbc.decrypt(&pcbc.encrypt(plain).unwrap()).unwrap(), plain);
+    }
+
+    #[test]
+    fn pcbc_error_propagates_unlike_cbc() {
+        let builder = Builder::new(KEY)
+            .unwrap()
+            .iv(&[42; 16])
+            .unwrap()
+            .padding(Padding::NoPadding);
+        let plain = [0; 64];
+
+        let cbc = builder.cbc();
+        let mut enc = cbc.encrypt(&plain).unwrap();
+        enc[0] ^= 1;
+        let dec = cbc.decrypt(&enc).unwrap();
+        // only the damaged block and the next one are affected
+        assert_eq!(dec[32..], plain[32..]);
+
+        let pcbc = builder.pcbc();
+        let mut enc = pcbc.encrypt(&plain).unwrap();
+        enc[0] ^= 1;
+        let dec = pcbc.decrypt(&enc).unwrap();
+        // every following block is garbled
+        for (dec_block, plain_block) in dec.chunks(BLOCK_SIZE).zip(plain.chunks(BLOCK_SIZE)) {
+            assert_ne!(dec_block, plain_block);
+        }
+    }
+
+    #[test]
+    fn ctr_cryptopals_layout() {
+        // https://cryptopals.com/sets/3/challenges/18
+        let enc = base64::decode(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        )
+        .unwrap();
+        let ctr = Builder::new(KEY)
+            .unwrap()
+            .ctr(CounterLayout::LittleEndian { nonce: 0 });
+
+        let plain = String::from_utf8(ctr.decrypt(&enc).unwrap()).unwrap();
+        assert!(plain.starts_with("Yo, VIP Let's kick it Ice, Ice, baby"));
+    }
+
+    #[test]
+    fn ctr_nist_known_answer() {
+        // NIST SP 800-38A, F.5.1 CTR-AES128.Encrypt
+        let key = "2b7e151628aed2a6abf7158809cf4f3c".parse_hex().unwrap();
+        let plain = "6bc1bee22e409f96e93d7e117393172a\
+                     ae2d8a571e03ac9c9eb76fac45af8e51\
+                     30c81c46a35ce411e5fbc1191a0a52ef\
+                     f69f2445df4f9b17ad2b417be66c3710"
+            .parse_hex()
+            .unwrap();
+        let expected = "874d6191b620e3261bef6864990db6ce\
+                        9806f66b7970fdff8617187bb9fffdff\
+                        5ae4df3edbd5d35e5b4f09020db03eab\
+                        1e031dda2fbe03d1792170a0f3009cee"
+            .parse_hex()
+            .unwrap();
+
+        // the initial counter block is f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff
+        let mut nonce = [0; 12];
+        nonce.copy_from_slice(&"f0f1f2f3f4f5f6f7f8f9fafb".parse_hex().unwrap());
+        let ctr = Builder::new(&key)
+            .unwrap()
+            .ctr(CounterLayout::Nonce96 { nonce });
+        let first_block = u64::from(0xfcfd_feff_u32) * BLOCK_SIZE as u64;
+
+        assert_eq!(ctr.apply_at(&plain, first_block), expected);
+    }
+
+    #[test]
+    fn ctr_layouts_differ() {
+        let le = Builder::new(KEY)
+            .unwrap()
+            .ctr(CounterLayout::LittleEndian { nonce: 1 });
+        let be = Builder::new(KEY)
+            .unwrap()
+            .ctr(CounterLayout::BigEndian { nonce: 1 });
+
+        let plain = [0; 32];
+        assert_ne!(le.encrypt(&plain).unwrap(), be.encrypt(&plain).unwrap());
+        assert_eq!(le.decrypt(&le.encrypt(&plain).unwrap()).unwrap(), plain);
+    }
+
+    #[test]
+    fn ctr_keystream_seek() {
+        let ctr = Builder::new(KEY)
+            .unwrap()
+            .ctr(CounterLayout::BigEndian { nonce: 42 });
+        let full: Vec<_> = ctr.keystream().take(100).collect();
+
+        for position in 0..60 {
+            let mut keystream = ctr.keystream();
+            keystream.seek(position);
+            assert_eq!(keystream.position(), position);
+
+            let part: Vec<_> = keystream.take(40).collect();
+            #[allow(clippy::cast_possible_truncation)]
+            let position = position as usize;
+            assert_eq!(part, &full[position..position + 40]);
+        }
+    }
+
+    fn streaming_modes(builder: &Builder) -> Vec<Box<dyn StreamingMode>> {
+        vec![
+            Box::new(builder.ecb()),
+            Box::new(builder.cbc()),
+            Box::new(builder.pcbc()),
+            Box::new(builder.cfb8()),
+            Box::new(builder.cfb128()),
+            Box::new(builder.ofb()),
+            Box::new(builder.ctr(CounterLayout::BigEndian { nonce: 7 })),
+        ]
+    }
+
+    #[test]
+    fn streaming_matches_the_whole_data() {
+        let builder = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap();
+        let data: Vec<u8> = (0..100).collect();
+
+        for mode in streaming_modes(&builder) {
+            let mode = &*mode;
+            for &size in &[0, 1, 15, 16, 17, 48, 100] {
+                let plain = &data[..size];
+                let enc = mode.encrypt(plain).unwrap();
+
+                let mut encryptor = Encryptor::new(mode, Vec::new());
+                for piece in plain.chunks(7) {
+                    encryptor.write_all(piece).unwrap();
+                }
+                assert_eq!(encryptor.finish().unwrap(), enc);
+
+                // the short reads from the inner reader and into the buffer
+                let (first, second) = enc.split_at(enc.len() / 3);
+                let mut decryptor = Decryptor::new(mode, first.chain(second));
+                let mut dec = Vec::new();
+                let mut buf = [0; 5];
+                loop {
+                    let len = decryptor.read(&mut buf).unwrap();
+                    if len == 0 {
+                        break;
+                    }
+                    dec.extend_from_slice(&buf[..len]);
+                }
+                assert_eq!(dec, plain);
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_errors() {
+        let builder = Builder::new(KEY).unwrap();
+        let cbc = builder.cbc();
+
+        let err = Decryptor::new(&cbc, &[0; 20][..])
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("20"));
+
+        let unpadded = builder
+            .clone()
+            .padding(Padding::NoPadding)
+            .cbc()
+            .encrypt(&[0; 32])
+            .unwrap();
+        let err = Decryptor::new(&cbc, unpadded.as_slice())
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut encryptor = Encryptor::new(builder.padding(Padding::NoPadding).ecb(), Vec::new());
+        encryptor.write_all(&[0; 20]).unwrap();
+        assert_eq!(
+            encryptor.finish().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    /// Takes up to 7 bytes at once and fails on the given calls
+    struct Flaky<T> {
+        inner: T,
+        calls: usize,
+        failures: &'static [usize],
+    }
+
+    impl<T> Flaky<T> {
+        fn new(inner: T, failures: &'static [usize]) -> Self {
+            Self {
+                inner,
+                calls: 0,
+                failures,
+            }
+        }
+
+        fn next_call(&mut self) -> io::Result<()> {
+            self.calls += 1;
+            if self.failures.contains(&self.calls) {
+                Err(io::Error::new(io::ErrorKind::Other, "failed"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl<T: Write> Write for Flaky<T> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.next_call()?;
+            self.inner.write(&buf[..buf.len().min(7)])
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl<T: Read> Read for Flaky<T> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.next_call()?;
+            let len = buf.len().min(7);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn streaming_retry_after_the_inner_failure() {
+        let builder = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap();
+        let data: Vec<u8> = (0..40).collect();
+
+        for mode in streaming_modes(&builder) {
+            let mode = &*mode;
+            let enc = mode.encrypt(&data).unwrap();
+
+            // in the middle of the block and then right on the next write
+            let mut encryptor = Encryptor::new(mode, Flaky::new(Vec::new(), &[3, 4]));
+            let mut rest = &data[..];
+            let mut failures = 0;
+            while !rest.is_empty() {
+                match encryptor.write(rest) {
+                    Ok(len) => rest = &rest[len..],
+                    Err(_) => failures += 1,
+                }
+            }
+            assert_eq!(failures, 1);
+            assert_eq!(encryptor.finish().unwrap().inner, enc);
+
+            let mut decryptor = Decryptor::new(mode, Flaky::new(enc.as_slice(), &[2, 5]));
+            let mut dec = Vec::new();
+            let mut buf = [0; 5];
+            let mut failures = 0;
+            loop {
+                match decryptor.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(len) => dec.extend_from_slice(&buf[..len]),
+                    Err(_) => failures += 1,
+                }
+            }
+            assert_eq!(failures, 2);
+            assert_eq!(dec, data);
+        }
+    }
+
+    #[test]
+    fn keystream_position_wraps_around() {
+        let ctr = Builder::new(KEY)
+            .unwrap()
+            .ctr(CounterLayout::BigEndian { nonce: 7 });
+        let mut keystream = ctr.keystream_at(u64::MAX - 1);
+        assert_eq!(keystream.position(), u64::MAX - 1);
+        assert_eq!(keystream.by_ref().take(20).count(), 20);
+        assert_eq!(keystream.position(), 18);
+    }
+
+    #[test]
+    fn in_place_without_padding() {
+        let builder = Builder::new(KEY).unwrap().iv(&[42; 16]).unwrap();
+        let no_padding = builder.clone().padding(Padding::NoPadding);
+        let plain = [3; 48];
+
+        let mut data = plain;
+        let cbc = builder.cbc();
+        cbc.encrypt_in_place(&mut data).unwrap();
+        assert_eq!(data.to_vec(), no_padding.cbc().encrypt(&plain).unwrap());
+        cbc.decrypt_in_place(&mut data).unwrap();
+        assert_eq!(data, plain);
+
+        let mut data = [3; 20];
+        assert_eq!(
+            cbc.encrypt_in_place(&mut data),
+            Err(Error::PartialBlock { size: 20 })
+        );
+
+        let ofb = builder.ofb();
+        ofb.encrypt_in_place(&mut data).unwrap();
+        assert_eq!(data.to_vec(), ofb.encrypt(&[3; 20]).unwrap());
+    }
+}
@@ -0,0 +1,275 @@
+//! Breaking the keystream reuse (the two-time and many-time pad) by crib dragging.
+//!
+//! XOR-ing two ciphertexts encrypted with the same keystream cancels the keystream out
+//! leaving the XOR of two plaintexts. Sliding a probable word (the crib) across it
+//! reveals the fragment of the other plaintext where the crib is guessed right.
+//! Every confirmed guess uncovers the piece of keystream and of all the other plaintexts,
+//! suggesting the next cribs.
+
+use crate::{score::Scorer, Error, Result};
+
+/// XOR of two ciphertexts (truncated to the shortest one)
+pub fn xor_pair(first: &[u8], second: &[u8]) -> Vec<u8> {
+    first.iter().zip(second).map(|(a, b)| a ^ b).collect()
+}
+
+/// Slide the crib across the XOR of two ciphertexts.
+///
+/// Returns the positions giving the valid UTF-8 text in the other plaintext
+/// along with this text and its score, the most English-like first.
+pub fn drag_pair<S>(xored: &[u8], crib: &[u8], scorer: &S) -> Vec<(usize, String, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    if crib.is_empty() || crib.len() > xored.len() {
+        return vec![];
+    }
+
+    let mut candidates: Vec<_> = xored
+        .windows(crib.len())
+        .enumerate()
+        .filter_map(|(position, window)| {
+            let revealed = String::from_utf8(xor_pair(window, crib)).ok()?;
+            let score = scorer.score(&revealed);
+            Some((position, revealed, score))
+        })
+        .collect();
+
+    candidates.sort_by_key(|(_position, _revealed, score)| *score);
+    candidates
+}
+
+/// The guess that the crib is a part of some plaintext at some position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CribMatch {
+    /// The index of the ciphertext supposed to contain the crib
+    pub cipher_text: usize,
+    pub position: usize,
+    /// The fragments of the other plaintexts revealed by the crib
+    pub revealed: Vec<(usize, String)>,
+    pub score: u64,
+}
+
+/// Many-time pad solver collecting the known keystream bytes guess by guess
+#[derive(Debug, Clone)]
+pub struct CribDragger {
+    cipher_texts: Vec<Vec<u8>>,
+    keystream: Vec<Option<u8>>,
+}
+
+impl CribDragger {
+    pub fn new(cipher_texts: Vec<Vec<u8>>) -> Self {
+        let max_len = cipher_texts.iter().map(Vec::len).max().unwrap_or(0);
+        Self {
+            cipher_texts,
+            keystream: vec![None; max_len],
+        }
+    }
+
+    pub fn keystream(&self) -> &[Option<u8>] {
+        &self.keystream
+    }
+
+    /// Try the crib at every position of every ciphertext.
+    ///
+    /// Only the guesses revealing the valid UTF-8 text in all the other
+    /// (long enough) plaintexts are returned, the most English-like first.
+    pub fn drag<S>(&self, crib: &[u8], scorer: &S) -> Vec<CribMatch>
+    where
+        S: Scorer + ?Sized,
+    {
+        let mut candidates = vec![];
+        for (index, cipher_text) in self.cipher_texts.iter().enumerate() {
+            if crib.is_empty() || crib.len() > cipher_text.len() {
+                continue;
+            }
+
+            for position in 0..=cipher_text.len() - crib.len() {
+                let keystream = xor_pair(&cipher_text[position..], crib);
+                if let Some(candidate) = self.reveal(index, position, &keystream, scorer) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        candidates.sort_by_key(|candidate| candidate.score);
+        candidates
+    }
+
+    fn reveal<S>(
+        &self,
+        index: usize,
+        position: usize,
+        keystream: &[u8],
+        scorer: &S,
+    ) -> Option<CribMatch>
+    where
+        S: Scorer + ?Sized,
+    {
+        let mut revealed = vec![];
+        for (other_index, other) in self.cipher_texts.iter().enumerate() {
+            if other_index == index || other.len() <= position {
+                continue;
+            }
+
+            let text = String::from_utf8(xor_pair(&other[position..], keystream)).ok()?;
+            revealed.push((other_index, text));
+        }
+
+        let all_revealed: Vec<_> = revealed.iter().map(|(_, text)| text.as_str()).collect();
+        let score = scorer.score(&all_revealed.join(" "));
+        Some(CribMatch {
+            cipher_text: index,
+            position,
+            revealed,
+            score,
+        })
+    }
+
+    /// Accept the crib as the plaintext of the given ciphertext at the given position
+    /// and remember the uncovered keystream. The latest guess wins over the previous ones.
+    ///
+    /// # Errors
+    /// The crib does not fit in the ciphertext
+    pub fn place(&mut self, cipher_text: usize, position: usize, crib: &[u8]) -> Result<()> {
+        let data = self
+            .cipher_texts
+            .get(cipher_text)
+            .ok_or(Error::PositionOutOfRange {
+                position: cipher_text,
+                len: self.cipher_texts.len(),
+            })?;
+        if position + crib.len() > data.len() {
+            return Err(Error::PositionOutOfRange {
+                position: position + crib.len(),
+                len: data.len(),
+            });
+        }
+
+        let keystream = xor_pair(&data[position..], crib);
+        for (known, byte) in self.keystream[position..].iter_mut().zip(keystream) {
+            *known = Some(byte);
+        }
+        Ok(())
+    }
+
+    /// Decrypt the ciphertext with the known part of the keystream
+    pub fn decrypt(&self, cipher_text: usize) -> Vec<Option<u8>> {
+        self.cipher_texts
+            .get(cipher_text)
+            .map_or_else(Vec::new, |data| {
+                data.iter()
+                    .zip(&self.keystream)
+                    .map(|(byte, key)| key.map(|key| byte ^ key))
+                    .collect()
+            })
+    }
+
+    /// The partially decrypted plaintext with the unknown symbols replaced
+    pub fn render(&self, cipher_text: usize, unknown: char) -> String {
+        self.decrypt(cipher_text)
+            .into_iter()
+            .map(|byte| byte.map_or(unknown, char::from))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        score::{ByteFrequency, NGrams},
+        BytesCryptoExt, StreamCipher,
+    };
+
+    use super::*;
+
+    const FIRST: &str = "Attack at dawn, the bridge is ready";
+    const SECOND: &str = "We meet at the old mill by the river";
+    const THIRD: &str = "Bring the maps and the radio with you";
+
+    fn two_time_pad() -> (Vec<u8>, Vec<Vec<u8>>) {
+        let keystream = Vec::generate_random(64);
+        let cipher_texts = [FIRST, SECOND, THIRD]
+            .iter()
+            .map(|plain| plain.xor_ref(keystream.iter()))
+            .collect();
+        (keystream, cipher_texts)
+    }
+
+    #[test]
+    fn drag_the_pair() {
+        let (_keystream, cipher_texts) = two_time_pad();
+        let xored = xor_pair(&cipher_texts[0], &cipher_texts[1]);
+        assert_eq!(xored.len(), FIRST.len());
+
+        let candidates = drag_pair(&xored, b" the ", &ByteFrequency::default());
+        // " the " in the first text at 15 reveals the second text there and vice versa
+        assert!(candidates
+            .iter()
+            .any(|(position, revealed, _)| *position == 15 && revealed == &SECOND[15..20]));
+        assert!(candidates
+            .iter()
+            .any(|(position, revealed, _)| *position == 10 && revealed == &FIRST[10..15]));
+        assert!(candidates.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+    }
+
+    #[test]
+    fn drag_the_crib_too_long() {
+        assert!(drag_pair(&[1, 2], b"the", &ByteFrequency::default()).is_empty());
+        assert!(drag_pair(&[1, 2], b"", &ByteFrequency::default()).is_empty());
+    }
+
+    #[test]
+    fn many_time_pad() {
+        let (keystream, cipher_texts) = two_time_pad();
+        let mut dragger = CribDragger::new(cipher_texts);
+
+        let candidates = dragger.drag(b" the ", NGrams::english_quadgrams());
+        let right_guess = candidates
+            .iter()
+            .find(|candidate| candidate.cipher_text == 2 && candidate.position == 5)
+            .unwrap();
+        assert_eq!(
+            right_guess.revealed,
+            vec![
+                (0, FIRST[5..10].to_string()),
+                (1, SECOND[5..10].to_string())
+            ]
+        );
+
+        dragger.place(2, 5, b" the ").unwrap();
+        let known: Vec<_> = keystream[5..10].iter().copied().map(Some).collect();
+        assert_eq!(&dragger.keystream()[5..10], known.as_slice());
+        assert_eq!(
+            dragger.render(0, '_'),
+            format!("_____{}{}", &FIRST[5..10], "_".repeat(FIRST.len() - 10))
+        );
+
+        // the revealed "k at " suggests the beginning of the first text
+        dragger.place(0, 0, b"Attack at dawn").unwrap();
+        assert!(dragger.render(1, '_').starts_with("We meet at the"));
+        assert!(dragger.render(2, '_').starts_with("Bring the maps"));
+    }
+
+    #[test]
+    fn place_out_of_range() {
+        let (_keystream, cipher_texts) = two_time_pad();
+        let mut dragger = CribDragger::new(cipher_texts);
+
+        assert_eq!(
+            dragger.place(3, 0, b"the"),
+            Err(Error::PositionOutOfRange {
+                position: 3,
+                len: 3
+            })
+        );
+        assert_eq!(
+            dragger.place(0, FIRST.len() - 2, b"the"),
+            Err(Error::PositionOutOfRange {
+                position: FIRST.len() + 1,
+                len: FIRST.len()
+            })
+        );
+        assert!(dragger.keystream().iter().all(Option::is_none));
+    }
+}
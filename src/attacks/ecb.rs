@@ -0,0 +1,518 @@
+//! Byte-at-a-time decryption of the secret data encrypted
+//! by the ECB oracle along with the attacker's payload (challenges 12 and 14):
+//!
+//! `oracle(payload) = ECB(random-prefix || payload || secret-suffix)`
+
+use std::collections::HashSet;
+
+use log::{debug, trace};
+
+use crate::{logging, oracle::Oracle, Error, Result};
+
+/// Give up the block size detection after the payload of this size
+const MAX_BLOCK_SIZE: usize = 256;
+
+/// Everything revealed about the data surrounding the attacker's payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered {
+    pub block_size: usize,
+    /// The size of the unknown data prepended to the payload
+    pub prefix_len: usize,
+    pub suffix_len: usize,
+    /// The unknown data appended to the payload
+    pub suffix: Vec<u8>,
+}
+
+/// Reveal the secret suffix encrypted by the oracle
+///
+/// # Errors
+/// - the block size cannot be detected
+/// - the oracle does not use the ECB mode
+/// - some byte of the suffix cannot be matched
+/// - the oracle refuses to answer
+pub fn byte_at_a_time<O>(oracle: O) -> Result<Recovered>
+where
+    O: Oracle<Answer = Vec<u8>>,
+{
+    EcbBreaking::new(oracle)?.recover()
+}
+
+/// Feed the growing payload to the oracle until the ciphertext jumps to the next block.
+///
+/// Returns the size of the block and the total size of the fixed data
+/// (the prefix and the suffix) encrypted along with the payload.
+///
+/// # Errors
+/// - the ciphertext size does not change in a predictable manner
+/// - the oracle refuses to answer
+pub fn detect_block_size<O>(oracle: &O) -> Result<(usize, usize)>
+where
+    O: Oracle<Answer = Vec<u8>>,
+{
+    let empty_enc_size = oracle.query(&[])?.len();
+    debug!(
+        target: logging::ATTACK,
+        "Empty payload ciphertext size: {}", empty_enc_size
+    );
+
+    for padding_size in 1..=MAX_BLOCK_SIZE {
+        let payload = same_symbols_data(padding_size);
+        let enc_size = oracle.query(&payload)?.len();
+        trace!(
+            target: logging::ATTACK,
+            "The size of the ciphertext of the payload {:?} is {}",
+            payload, enc_size,
+        );
+
+        if enc_size != empty_enc_size {
+            let block_size = enc_size
+                .checked_sub(empty_enc_size)
+                .ok_or(Error::UnknownBlockSize)?;
+            let fixed_data_size = empty_enc_size
+                .checked_sub(padding_size)
+                .ok_or(Error::UnknownBlockSize)?;
+            return Ok((block_size, fixed_data_size));
+        }
+    }
+
+    Err(Error::UnknownBlockSize)
+}
+
+/// The number of the blocks which are the same as some previous one.
+/// The ECB mode leaks the repeated plaintext blocks this way (challenge 8).
+pub fn repeated_blocks(data: &[u8], block_size: usize) -> usize {
+    if block_size == 0 {
+        return 0;
+    }
+
+    let mut seen = HashSet::new();
+    data.chunks_exact(block_size)
+        .filter(|block| !seen.insert(*block))
+        .count()
+}
+
+fn same_symbols_data(size: usize) -> Vec<u8> {
+    vec![b'A'; size]
+}
+
+/// A set of tools to reveal some information about plaintext
+/// encrypted with the block cipher in ECB mode
+pub struct EcbBreaking<O>
+where
+    O: Oracle<Answer = Vec<u8>>,
+{
+    oracle: O,
+    block_size: usize,
+    fixed_parts_size: usize,
+}
+
+impl<O> EcbBreaking<O>
+where
+    O: Oracle<Answer = Vec<u8>>,
+{
+    /// # Errors
+    /// - the block size cannot be detected
+    /// - the oracle refuses to answer
+    pub fn new(oracle: O) -> Result<Self> {
+        let (block_size, fixed_parts_size) = detect_block_size(&oracle)?;
+        Ok(Self {
+            oracle,
+            block_size,
+            fixed_parts_size,
+        })
+    }
+
+    pub const fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub const fn oracle(&self) -> &O {
+        &self.oracle
+    }
+
+    pub fn into_oracle(self) -> O {
+        self.oracle
+    }
+
+    /// The number of blocks occupied by the prefix (including the partial one).
+    ///
+    /// # Errors
+    /// The oracle does not use the ECB mode
+    pub fn prefix_blocks(&self) -> Result<usize> {
+        let blocks_to_verify = 10;
+
+        // at least X+2 blocks of data should be used, to allow random bytes padding
+        // to eat some data from the beginning (first block) and the end (last block)
+        // and detect at least X successive identical blocks
+        let payload_size = self.block_size * (blocks_to_verify + 2);
+        let enc_blocks = self.encrypt_same_symbols(payload_size)?;
+
+        let mut first_of_equal_blocks = 0;
+        for (block_number, (block, next_block)) in
+            enc_blocks.iter().zip(&enc_blocks[1..]).enumerate()
+        {
+            if block == next_block {
+                if (block_number - first_of_equal_blocks) == blocks_to_verify - 1 {
+                    return Ok(first_of_equal_blocks);
+                }
+            } else {
+                first_of_equal_blocks = block_number + 1;
+            }
+        }
+
+        Err(Error::NotEcb)
+    }
+
+    /// The number of payload bytes required to fill the last block of the prefix
+    fn prefix_padding_size(&self, prefix_blocks: usize) -> Result<usize> {
+        // Take two blocks of 'A' to get the 'A'-block encrypted representation.
+        // E.g. prefix_blocks = 5
+        //
+        //   data | --- prefix --- |AAA|AAA|
+        // blocks | 0 | 1 | 2 | 3 | 4 | 5 | 6 |
+        let enc_blocks = self.encrypt_same_symbols(self.block_size * 2)?;
+        let a_block = enc_blocks.get(prefix_blocks).ok_or(Error::NotEcb)?;
+
+        //                    find this gap's size
+        //                          VV
+        //   data | --- prefix --- |AA|AAA|
+        // blocks | 0 | 1 | 2 | 3 | 4 | 5 |
+        for pad_size in 0..self.block_size * 2 {
+            let enc_blocks = self.encrypt_same_symbols(pad_size)?;
+            if enc_blocks.get(prefix_blocks) == Some(a_block) {
+                return Ok(pad_size % self.block_size);
+            }
+        }
+
+        Err(Error::NotEcb)
+    }
+
+    fn prefix_components(&self) -> Result<(usize, usize)> {
+        let prefix_blocks = self.prefix_blocks()?;
+        let prefix_padding_size = self.prefix_padding_size(prefix_blocks)?;
+        Ok((prefix_blocks, prefix_padding_size))
+    }
+
+    /// # Errors
+    /// The oracle does not use the ECB mode
+    pub fn prefix_len(&self) -> Result<usize> {
+        let (prefix_blocks, prefix_padding_size) = self.prefix_components()?;
+        Ok(prefix_blocks * self.block_size - prefix_padding_size)
+    }
+
+    /// # Errors
+    /// The oracle does not use the ECB mode
+    pub fn suffix_len(&self) -> Result<usize> {
+        self.fixed_parts_size
+            .checked_sub(self.prefix_len()?)
+            .ok_or(Error::NotEcb)
+    }
+
+    fn detect_byte(
+        &self,
+        short_payload: &[u8],
+        target_block_number: usize,
+        expected_block: &[u8],
+    ) -> Result<Option<u8>> {
+        for i in 0..=u8::MAX {
+            let mut crafted_payload = short_payload.to_vec();
+            crafted_payload.push(i);
+
+            let enc = self.encrypt_blocks(&crafted_payload)?;
+            if enc.get(target_block_number).map(Vec::as_slice) == Some(expected_block) {
+                trace!(
+                    target: logging::ATTACK,
+                    "The result of encrypting the block {:?} is {:?} (in the {}-th position)",
+                    crafted_payload, expected_block, target_block_number,
+                );
+                return Ok(Some(i));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn encrypt_blocks(&self, data: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let enc_blocks = self.oracle.query(data)?;
+        Ok(enc_blocks
+            .chunks(self.block_size)
+            .map(<[_]>::to_vec)
+            .collect())
+    }
+
+    fn encrypt_same_symbols(&self, size: usize) -> Result<Vec<Vec<u8>>> {
+        let payload = same_symbols_data(size);
+        self.encrypt_blocks(&payload)
+    }
+
+    /// Reveal the suffix byte by byte
+    ///
+    /// # Errors
+    /// - the oracle does not use the ECB mode
+    /// - some byte of the suffix cannot be matched
+    /// - the oracle refuses to answer
+    pub fn recover(&self) -> Result<Recovered> {
+        let (prefix_blocks, prefix_padding_size) = self.prefix_components()?;
+        let prefix_len = prefix_blocks * self.block_size - prefix_padding_size;
+        let suffix_len = self
+            .fixed_parts_size
+            .checked_sub(prefix_len)
+            .ok_or(Error::NotEcb)?;
+
+        let mut suffix = Vec::with_capacity(suffix_len);
+
+        for byte_number in 0..suffix_len {
+            let byte_offset = byte_number % self.block_size;
+            let block_number = byte_number / self.block_size;
+            debug!(
+                target: logging::ATTACK,
+                "Decrypting the suffix's {}-th byte. Offset is {}. Block number is {}",
+                byte_number, byte_offset, block_number
+            );
+            let one_byte_shorter = self.block_size - byte_offset - 1;
+            let short_payload = same_symbols_data(prefix_padding_size + one_byte_shorter);
+
+            let short_template = self.encrypt_blocks(&short_payload)?;
+            let short_block = short_template
+                .get(prefix_blocks + block_number)
+                .ok_or(Error::NotEcb)?;
+
+            // always left only one unrevealed byte in the first block
+            let brute_force_payload = if block_number == 0 {
+                let mut payload = short_payload.clone();
+                payload.extend_from_slice(&suffix[..byte_number]);
+                payload
+            } else {
+                let known_suffix_part = &suffix[byte_number - self.block_size + 1..byte_number];
+                let mut payload = same_symbols_data(prefix_padding_size);
+                payload.extend_from_slice(known_suffix_part);
+                payload
+            };
+            debug_assert_eq!(
+                brute_force_payload.len(),
+                prefix_padding_size + self.block_size - 1
+            );
+
+            let detected_byte = self
+                .detect_byte(&brute_force_payload, prefix_blocks, short_block)?
+                .ok_or(Error::NoCandidate)?;
+            suffix.push(detected_byte);
+        }
+
+        Ok(Recovered {
+            block_size: self.block_size,
+            prefix_len,
+            suffix_len,
+            suffix,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+    use rand::Rng;
+
+    use crate::{
+        aes_cypher::{BlockMode, Builder, Cbc, Ecb},
+        oracle::Metered,
+        random, BytesCryptoExt,
+    };
+
+    use super::*;
+
+    lazy_static! {
+        static ref KEY: Vec<u8> = Vec::generate_random(16);
+        static ref CIPHER: Ecb = Builder::new(&KEY).unwrap().ecb();
+        static ref CBC_CIPHER: Cbc = Builder::new(&KEY).unwrap().cbc();
+    }
+
+    fn encrypt_with_prefix_and_suffix(
+        prefix: Vec<u8>,
+        suffix: Vec<u8>,
+    ) -> impl Fn(&[u8]) -> Vec<u8> {
+        move |data| {
+            let mut plain = prefix.clone();
+            plain.extend_from_slice(data);
+            plain.extend_from_slice(&suffix);
+
+            CIPHER.encrypt(&plain).unwrap()
+        }
+    }
+
+    fn encrypt_with_prefix(prefix: Vec<u8>) -> impl Fn(&[u8]) -> Vec<u8> {
+        encrypt_with_prefix_and_suffix(prefix, vec![])
+    }
+
+    #[test]
+    fn count_repeated_blocks() {
+        let data = b"YELLOW SUBMARINEyellow submarineYELLOW SUBMARINEYELLOW SUBMARINE!";
+        assert_eq!(repeated_blocks(data, 16), 2);
+        assert_eq!(repeated_blocks(data, 15), 0);
+        assert_eq!(repeated_blocks(data, 0), 0);
+    }
+
+    #[test]
+    fn detect_block_size_no_additional_data() {
+        let f = encrypt_with_prefix(vec![]);
+        let ecb = EcbBreaking::new(f).unwrap();
+        assert_eq!(ecb.block_size, 16);
+        assert_eq!(ecb.fixed_parts_size, 0);
+    }
+
+    #[test]
+    fn detect_block_size_random_prefix() {
+        let mut rng = random::rng();
+        for _i in 0..10 {
+            let prefix_size = rng.gen_range(100, 1000);
+            let f = encrypt_with_prefix(vec![42; prefix_size]);
+
+            let ecb = EcbBreaking::new(f).unwrap();
+            assert_eq!(ecb.block_size, 16);
+            assert_eq!(ecb.fixed_parts_size, prefix_size);
+        }
+    }
+
+    #[test]
+    fn detect_block_size_random_prefix_and_suffix() {
+        let mut rng = random::rng();
+        for _i in 0..10 {
+            let prefix_size = rng.gen_range(100, 1000);
+            let suffix_size = rng.gen_range(100, 1000);
+            let f = encrypt_with_prefix_and_suffix(vec![42; prefix_size], vec![28; suffix_size]);
+
+            let ecb = EcbBreaking::new(f).unwrap();
+            assert_eq!(ecb.block_size, 16);
+            assert_eq!(ecb.fixed_parts_size, prefix_size + suffix_size);
+        }
+    }
+
+    #[test]
+    fn constant_output_has_no_block_size() {
+        let f = |_: &[u8]| vec![0; 16];
+        assert_eq!(detect_block_size(&f), Err(Error::UnknownBlockSize));
+    }
+
+    #[test]
+    fn check_mode_detect_no_prefix() {
+        let f = encrypt_with_prefix(vec![]);
+        let ecb = EcbBreaking::new(f).unwrap();
+
+        assert_eq!(ecb.prefix_blocks(), Ok(0));
+        assert_eq!(ecb.prefix_padding_size(0), Ok(0));
+    }
+
+    #[test]
+    fn check_mode_detect_minimal_prefix() {
+        let f = encrypt_with_prefix(vec![1]);
+        let ecb = EcbBreaking::new(f).unwrap();
+
+        assert_eq!(ecb.prefix_blocks(), Ok(1));
+        assert_eq!(ecb.prefix_padding_size(1), Ok(15));
+    }
+
+    #[test]
+    fn check_mode_detect_whole_block_prefix() {
+        let f = encrypt_with_prefix(vec![1; 16]);
+        let ecb = EcbBreaking::new(f).unwrap();
+
+        assert_eq!(ecb.prefix_blocks(), Ok(1));
+        assert_eq!(ecb.prefix_padding_size(1), Ok(0));
+    }
+
+    #[test]
+    fn check_mode_detect_several_blocks_prefix() {
+        let f = encrypt_with_prefix(vec![1; 48]);
+        let ecb = EcbBreaking::new(f).unwrap();
+
+        assert_eq!(ecb.prefix_blocks(), Ok(3));
+        assert_eq!(ecb.prefix_padding_size(3), Ok(0));
+    }
+
+    #[test]
+    fn check_mode_detect_uneven_blocks_prefix() {
+        for i in 49..=64 {
+            let f = encrypt_with_prefix(vec![1; i]);
+            let ecb = EcbBreaking::new(f).unwrap();
+
+            assert_eq!(ecb.prefix_blocks(), Ok(4));
+            assert_eq!(ecb.prefix_padding_size(4), Ok(64 - i));
+        }
+
+        let f = encrypt_with_prefix(vec![1; 65]);
+        let ecb = EcbBreaking::new(f).unwrap();
+
+        assert_eq!(ecb.prefix_blocks(), Ok(5));
+        assert_eq!(ecb.prefix_padding_size(5), Ok(15));
+    }
+
+    #[test]
+    fn check_prefix_size_invariants() {
+        let mut rng = random::rng();
+        for _i in 0..30 {
+            // TODO: increase the possible prefix to cover more than 10 blocks
+            let prefix_size = rng.gen_range(100, 160);
+
+            let f = encrypt_with_prefix(vec![255; prefix_size]);
+            let ecb = EcbBreaking::new(f).unwrap();
+
+            let (prefix_blocks, prefix_padding_size) = ecb.prefix_components().unwrap();
+            assert_eq!(
+                (prefix_padding_size + prefix_size),
+                prefix_blocks * ecb.block_size
+            );
+            assert_eq!(ecb.prefix_len(), Ok(prefix_size));
+            assert_eq!(ecb.suffix_len(), Ok(0));
+        }
+    }
+
+    #[test]
+    fn recover_the_suffix() {
+        let suffix = b"Rollin' in my 5.0\nWith my rag-top down".to_vec();
+        let f = encrypt_with_prefix_and_suffix(vec![7; 37], suffix.clone());
+
+        let recovered = byte_at_a_time(f).unwrap();
+        assert_eq!(
+            recovered,
+            Recovered {
+                block_size: 16,
+                prefix_len: 37,
+                suffix_len: suffix.len(),
+                suffix,
+            }
+        );
+    }
+
+    #[test]
+    fn replay_the_recorded_attack() {
+        let suffix = b"Did you stop? No, I just drove by".to_vec();
+        let f = encrypt_with_prefix_and_suffix(vec![7; 5], suffix.clone());
+
+        let ecb = EcbBreaking::new(Metered::new(f).recording()).unwrap();
+        let recovered = ecb.recover().unwrap();
+        assert_eq!(recovered.suffix, suffix);
+        let queries = ecb.oracle().queries();
+
+        let transcript = ecb.into_oracle().into_transcript();
+        assert_eq!(transcript.len(), queries);
+
+        let ecb = EcbBreaking::new(Metered::new(transcript.replay())).unwrap();
+        assert_eq!(ecb.recover(), Ok(recovered));
+        assert_eq!(ecb.oracle().queries(), queries);
+    }
+
+    #[test]
+    fn attack_within_the_budget() {
+        let f = encrypt_with_prefix_and_suffix(vec![], b"YELLOW".to_vec());
+        let oracle = Metered::new(f).budget(100);
+        assert_eq!(byte_at_a_time(oracle), Err(Error::QueryBudgetExceeded(100)));
+    }
+
+    #[test]
+    fn cbc_oracle_is_rejected() {
+        let f = |data: &[u8]| CBC_CIPHER.encrypt(data).unwrap();
+        let ecb = EcbBreaking::new(f).unwrap();
+        assert_eq!(ecb.block_size(), 16);
+        assert_eq!(ecb.recover(), Err(Error::NotEcb));
+    }
+}
@@ -0,0 +1,294 @@
+//! Breaking the CTR mode with the fixed nonce (challenges 19 and 20).
+//!
+//! All the ciphertexts are XOR-ed with the same keystream,
+//! so after truncating them to the common length, every column of bytes
+//! is encrypted with a single byte and the whole problem
+//! turns into the repeating-key XOR with the key of the ciphertext size.
+
+use crate::{score::Scorer, BytesCryptoExt, Error, Result, StreamCipher};
+
+/// The guessed byte of the keystream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeystreamByte {
+    pub value: u8,
+    /// From 0 (pure guess) to 1 (no doubt)
+    pub confidence: f64,
+    /// Set manually rather than guessed
+    pub corrected: bool,
+}
+
+/// The keystream shared by all the ciphertexts
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeystreamEstimate {
+    bytes: Vec<KeystreamByte>,
+}
+
+/// Estimate the keystream from the ciphertexts encrypted with the same key and nonce
+/// scoring the guessed plaintext columns with the scorer
+///
+/// # Errors
+/// No ciphertexts given
+pub fn break_fixed_nonce<T, S>(cipher_texts: &[T], scorer: &S) -> Result<KeystreamEstimate>
+where
+    T: AsRef<[u8]>,
+    S: Scorer + ?Sized,
+{
+    let common_len = cipher_texts
+        .iter()
+        .map(|cipher_text| cipher_text.as_ref().len())
+        .min()
+        .ok_or(Error::NoCandidate)?;
+
+    let bytes = (0..common_len)
+        .map(|position| {
+            let column: Vec<_> = cipher_texts
+                .iter()
+                .map(|cipher_text| cipher_text.as_ref()[position])
+                .collect();
+            guess_column_key(&column, scorer)
+        })
+        .collect();
+
+    Ok(KeystreamEstimate { bytes })
+}
+
+/// Single-byte XOR key for the column along with the confidence
+/// measured as the relative gap between the scores of the best and the second guesses.
+fn guess_column_key<S>(column: &[u8], scorer: &S) -> KeystreamByte
+where
+    S: Scorer + ?Sized,
+{
+    #![allow(clippy::cast_precision_loss)]
+
+    let column = column.to_vec();
+    let mut candidates = column.guess_the_single_char_xor_key(scorer);
+    // The keys differing in the 0x20 bit only swap the case of the letters
+    // and may get the same score, so prefer the mostly lowercase text.
+    candidates.sort_by_key(|(_key, plain, score)| {
+        let uppercase = plain.chars().filter(char::is_ascii_uppercase).count();
+        (*score, uppercase)
+    });
+    let mut candidates = candidates.into_iter();
+
+    let (value, confidence) = match (candidates.next(), candidates.next()) {
+        (None, _) => (0, 0.0),
+        (Some((key, _, _)), None) => (key, 1.0),
+        (Some((key, _, best)), Some((_, _, second))) => {
+            let gap = (second - best) as f64 / second.max(1) as f64;
+            (key, gap)
+        }
+    };
+
+    KeystreamByte {
+        value,
+        confidence,
+        corrected: false,
+    }
+}
+
+impl KeystreamEstimate {
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn bytes(&self) -> &[KeystreamByte] {
+        &self.bytes
+    }
+
+    pub fn keystream(&self) -> Vec<u8> {
+        self.bytes.iter().map(|byte| byte.value).collect()
+    }
+
+    /// The positions ordered from the least confident, the first candidates to correct
+    pub fn least_confident(&self) -> Vec<usize> {
+        let mut positions: Vec<_> = (0..self.len()).collect();
+        positions.sort_by(|&a, &b| {
+            self.bytes[a]
+                .confidence
+                .total_cmp(&self.bytes[b].confidence)
+        });
+        positions
+    }
+
+    /// Decrypt the ciphertext (or its prefix of the known keystream size)
+    pub fn decrypt(&self, cipher_text: &[u8]) -> Vec<u8> {
+        let len = cipher_text.len().min(self.len());
+        cipher_text[..len].xor_ref(self.bytes.iter().map(|byte| &byte.value))
+    }
+
+    /// Set the keystream byte at the given position
+    ///
+    /// # Errors
+    /// The position is beyond the estimated keystream
+    pub fn correct(&mut self, position: usize, value: u8) -> Result<()> {
+        let len = self.len();
+        let byte = self
+            .bytes
+            .get_mut(position)
+            .ok_or(Error::PositionOutOfRange { position, len })?;
+
+        *byte = KeystreamByte {
+            value,
+            confidence: 1.0,
+            corrected: true,
+        };
+        Ok(())
+    }
+
+    /// Fix the keystream so that the ciphertext decrypts
+    /// into the expected plaintext byte at the given position.
+    ///
+    /// E.g. if the line looks like `"Ro5lin' in my 5.0"`, correct the position 2 to be `b'l'`.
+    ///
+    /// # Errors
+    /// The position is beyond the ciphertext or the estimated keystream
+    pub fn correct_plain(&mut self, cipher_text: &[u8], position: usize, plain: u8) -> Result<()> {
+        let cipher_byte = cipher_text.get(position).ok_or(Error::PositionOutOfRange {
+            position,
+            len: cipher_text.len(),
+        })?;
+        self.correct(position, cipher_byte ^ plain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        aes_cypher::{BlockMode, Builder, CounterLayout},
+        score::ByteFrequency,
+    };
+
+    use super::*;
+
+    const LINES: [&str; 24] = [
+        "I have met them at close of day",
+        "Coming with vivid faces",
+        "From counter or desk among grey",
+        "Eighteenth-century houses.",
+        "I have passed with a nod of the head",
+        "Or polite meaningless words,",
+        "Or have lingered awhile and said",
+        "Polite meaningless words,",
+        "And thought before I had done",
+        "Of a mocking tale or a gibe",
+        "To please a companion",
+        "Around the fire at the club,",
+        "Being certain that they and I",
+        "But lived where motley is worn:",
+        "All changed, changed utterly:",
+        "A terrible beauty is born.",
+        "That woman's days were spent",
+        "In ignorant good will,",
+        "Her nights in argument",
+        "Until her voice grew shrill.",
+        "What voice more sweet than hers",
+        "When young and beautiful,",
+        "She rode to harriers?",
+        "This man had kept a school",
+    ];
+
+    fn encrypt_lines() -> (Vec<u8>, Vec<Vec<u8>>) {
+        let ctr = Builder::new(b"YELLOW SUBMARINE")
+            .unwrap()
+            .ctr(CounterLayout::LittleEndian { nonce: 0 });
+        let cipher_texts = LINES
+            .iter()
+            .map(|line| ctr.encrypt(line.as_bytes()).unwrap())
+            .collect();
+        let keystream = ctr.keystream().take(64).collect();
+        (keystream, cipher_texts)
+    }
+
+    #[test]
+    fn recover_most_of_the_keystream() {
+        let (keystream, cipher_texts) = encrypt_lines();
+        let estimate = break_fixed_nonce(&cipher_texts, &ByteFrequency::default()).unwrap();
+
+        let common_len = LINES.iter().map(|line| line.len()).min().unwrap();
+        assert_eq!(estimate.len(), common_len);
+
+        let guessed = estimate
+            .keystream()
+            .iter()
+            .zip(&keystream)
+            .filter(|(guess, real)| guess == real)
+            .count();
+        assert!(guessed * 10 >= common_len * 8, "{}/{}", guessed, common_len);
+
+        for byte in estimate.bytes() {
+            assert!((0.0..=1.0).contains(&byte.confidence));
+            assert!(!byte.corrected);
+        }
+    }
+
+    #[test]
+    fn manual_correction() {
+        let (keystream, cipher_texts) = encrypt_lines();
+        let mut estimate = break_fixed_nonce(&cipher_texts, &ByteFrequency::default()).unwrap();
+
+        for position in 0..estimate.len() {
+            estimate
+                .correct_plain(&cipher_texts[0], position, LINES[0].as_bytes()[position])
+                .unwrap();
+        }
+
+        assert_eq!(estimate.keystream(), &keystream[..estimate.len()]);
+        assert!(estimate.bytes().iter().all(|byte| byte.corrected));
+        for (line, cipher_text) in LINES.iter().zip(&cipher_texts) {
+            assert_eq!(
+                estimate.decrypt(cipher_text),
+                &line.as_bytes()[..estimate.len()]
+            );
+        }
+    }
+
+    #[test]
+    fn correction_out_of_range() {
+        let (_keystream, cipher_texts) = encrypt_lines();
+        let mut estimate = break_fixed_nonce(&cipher_texts, &ByteFrequency::default()).unwrap();
+        let len = estimate.len();
+
+        assert_eq!(
+            estimate.correct(len, 0),
+            Err(Error::PositionOutOfRange { position: len, len })
+        );
+        assert_eq!(
+            estimate.correct_plain(&[1, 2], 2, b'a'),
+            Err(Error::PositionOutOfRange {
+                position: 2,
+                len: 2
+            })
+        );
+    }
+
+    #[test]
+    fn least_confident_first() {
+        let (_keystream, cipher_texts) = encrypt_lines();
+        let mut estimate = break_fixed_nonce(&cipher_texts, &ByteFrequency::default()).unwrap();
+
+        let positions = estimate.least_confident();
+        assert_eq!(positions.len(), estimate.len());
+        let confidences: Vec<_> = positions
+            .iter()
+            .map(|&i| estimate.bytes()[i].confidence)
+            .collect();
+        assert!(confidences.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let worst = positions[0];
+        estimate.correct(worst, 42).unwrap();
+        assert_eq!(*estimate.least_confident().last().unwrap(), worst);
+    }
+
+    #[test]
+    fn no_cipher_texts() {
+        let cipher_texts: [Vec<u8>; 0] = [];
+        assert_eq!(
+            break_fixed_nonce(&cipher_texts, &ByteFrequency::default()),
+            Err(Error::NoCandidate)
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Attacks on the broken constructions from the challenges.
+
+pub mod crib_drag;
+pub mod ecb;
+pub mod fixed_nonce;
+pub mod padding_oracle;
+pub mod xor;
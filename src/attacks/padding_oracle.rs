@@ -0,0 +1,278 @@
+//! CBC padding oracle attack (challenge 17).
+//!
+//! The oracle answers whether the IV and the ciphertext decrypt
+//! into the plaintext with the valid PKCS#7 padding.
+//! The query for the [`Oracle`] is the IV followed by the ciphertext.
+
+use log::debug;
+use rand::seq::SliceRandom;
+
+use crate::{
+    aes_cypher::{BlockMode, Builder, Padding, BLOCK_SIZE},
+    logging,
+    oracle::Oracle,
+    random, BytesCryptoExt, Error, Result,
+};
+
+#[allow(clippy::cast_possible_truncation)]
+const PADDING_BLOCK_SIZE: u8 = BLOCK_SIZE as u8;
+
+/// The target: encrypts one of the secrets with the random IV
+/// and reveals nothing but the padding validity of any ciphertext.
+pub struct Service {
+    builder: Builder,
+    secrets: Vec<Vec<u8>>,
+}
+
+impl Service {
+    /// Use the random key to encrypt the secrets
+    ///
+    /// # Panics
+    /// Never: the random key always has the valid length
+    pub fn new(secrets: Vec<Vec<u8>>) -> Self {
+        let key = Vec::generate_random(BLOCK_SIZE);
+        let builder = Builder::new(&key).expect("The key has the valid length");
+        Self { builder, secrets }
+    }
+
+    /// Encrypt the randomly chosen secret.
+    /// Returns the IV and the ciphertext.
+    ///
+    /// # Panics
+    /// Never: the random IV and the padded secret always have the valid lengths
+    pub fn encrypt_random(&self) -> (Vec<u8>, Vec<u8>) {
+        let secret = self
+            .secrets
+            .choose(&mut random::rng())
+            .map_or(&[][..], Vec::as_slice);
+
+        let iv = Vec::generate_random(BLOCK_SIZE);
+        let cipher_text = self
+            .builder
+            .clone()
+            .iv(&iv)
+            .expect("The IV has the block size")
+            .cbc()
+            .encrypt(secret)
+            .expect("Padding always produces whole blocks");
+        (iv, cipher_text)
+    }
+
+    pub fn is_padding_valid(&self, iv: &[u8], cipher_text: &[u8]) -> bool {
+        let cbc = match self.builder.clone().iv(iv) {
+            Ok(builder) => builder.padding(Padding::NoPadding).cbc(),
+            Err(_) => return false,
+        };
+
+        cbc.decrypt(cipher_text)
+            .and_then(|plain| plain.pkcs7_padding_size(PADDING_BLOCK_SIZE))
+            .is_ok()
+    }
+}
+
+impl Oracle for Service {
+    type Answer = bool;
+
+    fn query(&self, input: &[u8]) -> Result<bool> {
+        if input.len() < BLOCK_SIZE {
+            return Ok(false);
+        }
+
+        let (iv, cipher_text) = input.split_at(BLOCK_SIZE);
+        Ok(self.is_padding_valid(iv, cipher_text))
+    }
+}
+
+/// Same as the [`decrypt`] for the oracle taking the IV and the ciphertext separately
+///
+/// # Errors
+/// See the [`decrypt`]
+pub fn decrypt_with<F>(oracle: F, iv: &[u8], cipher_text: &[u8]) -> Result<Vec<u8>>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    let oracle = |query: &[u8]| {
+        let (iv, cipher_text) = query.split_at(BLOCK_SIZE);
+        oracle(iv, cipher_text)
+    };
+    decrypt(&oracle, iv, cipher_text)
+}
+
+/// Recover the whole plaintext (without the padding) block by block.
+/// The first block is recovered by means of the IV.
+///
+/// # Errors
+/// - the IV is not of the block size
+/// - the ciphertext is not made of whole blocks
+/// - the oracle refuses to answer
+/// - the oracle gives no positive answer for some byte
+/// - the recovered plaintext has invalid padding
+pub fn decrypt<O>(oracle: &O, iv: &[u8], cipher_text: &[u8]) -> Result<Vec<u8>>
+where
+    O: Oracle<Answer = bool>,
+{
+    if iv.len() != BLOCK_SIZE {
+        return Err(Error::BadIvLength(iv.len()));
+    }
+    if cipher_text.is_empty() || cipher_text.len() % BLOCK_SIZE != 0 {
+        return Err(Error::PartialBlock {
+            size: cipher_text.len(),
+        });
+    }
+
+    let mut plain = Vec::with_capacity(cipher_text.len());
+    let mut previous = iv;
+    for (i, block) in cipher_text.chunks(BLOCK_SIZE).enumerate() {
+        debug!(
+            target: logging::ATTACK,
+            "Recovering the block {} of {}",
+            i + 1,
+            cipher_text.len() / BLOCK_SIZE
+        );
+        let intermediate = intermediate_block(oracle, block)?;
+        plain.extend(intermediate.iter().zip(previous).map(|(i, p)| i ^ p));
+        previous = block;
+    }
+
+    plain.unpad_pkcs7(PADDING_BLOCK_SIZE)?;
+    Ok(plain)
+}
+
+/// Recover the raw block cipher decryption of the block (before XOR-ing with the previous one)
+fn intermediate_block<O>(oracle: &O, block: &[u8]) -> Result<[u8; BLOCK_SIZE]>
+where
+    O: Oracle<Answer = bool>,
+{
+    let mut intermediate = [0; BLOCK_SIZE];
+    // the forged IV followed by the target block
+    let mut query = vec![0; BLOCK_SIZE];
+    query.extend_from_slice(block);
+
+    for pad in 1..=PADDING_BLOCK_SIZE {
+        let position = BLOCK_SIZE - usize::from(pad);
+        // force the already known bytes to decrypt into the current padding
+        for i in position + 1..BLOCK_SIZE {
+            query[i] = intermediate[i] ^ pad;
+        }
+
+        let mut found = None;
+        for guess in 0..=u8::MAX {
+            query[position] = guess;
+            if !oracle.query(&query)? {
+                continue;
+            }
+
+            if pad == 1 && position > 0 {
+                // The padding can be accidentally valid as the `\x02\x02` (or longer)
+                // if the preceding byte happen to decrypt into `\x02`.
+                // Changing the preceding byte breaks such a padding but not the `\x01`.
+                query[position - 1] ^= 1;
+                let still_valid = oracle.query(&query)?;
+                query[position - 1] ^= 1;
+                if !still_valid {
+                    continue;
+                }
+            }
+
+            found = Some(guess);
+            break;
+        }
+
+        let guess = found.ok_or(Error::NoCandidate)?;
+        intermediate[position] = guess ^ pad;
+    }
+
+    Ok(intermediate)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::oracle::Metered;
+
+    use super::*;
+
+    #[test]
+    fn recover_the_random_secrets() {
+        let secrets: Vec<Vec<u8>> = vec![
+            b"".to_vec(),
+            b"YELLOW SUBMARINE".to_vec(),
+            b"Burning 'em, if you ain't quick and nimble".to_vec(),
+        ];
+        let service = Service::new(secrets.clone());
+
+        for _i in 0..10 {
+            let (iv, cipher_text) = service.encrypt_random();
+            let plain = decrypt(&service, &iv, &cipher_text).unwrap();
+            assert!(secrets.contains(&plain));
+        }
+    }
+
+    #[test]
+    fn the_service_rejects_bad_padding() {
+        let service = Service::new(vec![b"ICE ICE BABY".to_vec()]);
+        let (iv, mut cipher_text) = service.encrypt_random();
+        assert!(service.is_padding_valid(&iv, &cipher_text));
+
+        cipher_text.pop();
+        assert!(!service.is_padding_valid(&iv, &cipher_text));
+        assert!(!service.is_padding_valid(&iv[1..], &cipher_text));
+    }
+
+    /// The CBC with the identity "block cipher",
+    /// so the intermediate block is the ciphertext block itself.
+    fn identity_cbc_oracle(iv: &[u8], cipher_text: &[u8]) -> bool {
+        let mut plain: Vec<_> = iv.iter().chain(cipher_text).copied().collect();
+        for i in (BLOCK_SIZE..plain.len()).rev() {
+            plain[i] ^= plain[i - BLOCK_SIZE];
+        }
+        plain.pkcs7_padding_size(PADDING_BLOCK_SIZE).is_ok()
+    }
+
+    #[test]
+    fn false_positive_double_padding() {
+        let first = b"Yo, VIP Let's ki";
+        let second = b"ICE ICE BABY\x04\x04\x04\x04";
+
+        // the first intermediate block ends with `\x02\x02`,
+        // so the forged byte 0x00 makes it look like a valid padding,
+        // long before the 0x03 giving the real `\x01`
+        let mut iv = [0; BLOCK_SIZE];
+        iv[14] = first[14] ^ 2;
+        iv[15] = first[15] ^ 2;
+
+        let c1: Vec<u8> = first.iter().zip(&iv).map(|(p, i)| p ^ i).collect();
+        assert_eq!(&c1[14..], b"\x02\x02");
+        let c2: Vec<u8> = second.iter().zip(&c1).map(|(p, c)| p ^ c).collect();
+        let cipher_text = [c1, c2].concat();
+
+        let plain = decrypt_with(identity_cbc_oracle, &iv, &cipher_text).unwrap();
+        assert_eq!(plain, b"Yo, VIP Let's kiICE ICE BABY");
+    }
+
+    #[test]
+    fn bad_input_is_rejected() {
+        let service = Service::new(vec![]);
+        assert_eq!(
+            decrypt(&service, &[0; 8], &[0; 16]),
+            Err(Error::BadIvLength(8))
+        );
+        assert_eq!(
+            decrypt(&service, &[0; 16], &[0; 20]),
+            Err(Error::PartialBlock { size: 20 })
+        );
+    }
+
+    #[test]
+    fn queries_count() {
+        let service = Service::new(vec![b"YELLOW SUBMARINE".to_vec()]);
+        let (iv, cipher_text) = service.encrypt_random();
+
+        let oracle = Metered::new(service);
+        assert_eq!(
+            decrypt(&oracle, &iv, &cipher_text).unwrap(),
+            b"YELLOW SUBMARINE"
+        );
+        // at most 256 guesses and one double check for each of two blocks
+        assert!(oracle.queries() <= 2 * (BLOCK_SIZE * 256 + 256));
+    }
+}
@@ -0,0 +1,362 @@
+//! Breaking the repeating-key XOR (challenge 6).
+//!
+//! First guess the size of the key, then transpose the ciphertext
+//! into the columns encrypted with a single byte each and break them one by one.
+
+use std::ops::RangeInclusive;
+
+use log::debug;
+
+use crate::{
+    hamming, logging,
+    plaintext::{AsciiText, PlaintextModel},
+    score::Scorer,
+    BytesCryptoExt, Result, StreamCipher,
+};
+
+/// The chance that two random bytes of English text are the same
+const ENGLISH_KAPPA: f64 = 0.065;
+/// The chance that two uniformly random bytes are the same
+const RANDOM_KAPPA: f64 = 1.0 / 256.0;
+
+/// The ways to guess the size of the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySizeMethod {
+    /// The average Hamming distance per byte between the consecutive blocks of the key size.
+    /// The lower the better.
+    Hamming,
+    /// The average index of coincidence of the columns encrypted with the same key byte.
+    /// The higher the better.
+    IndexOfCoincidence,
+    /// The distance from the key size estimated by the Friedman test
+    /// on the index of coincidence of the whole ciphertext. The lower the better.
+    Friedman,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    method: KeySizeMethod,
+    key_sizes: RangeInclusive<usize>,
+    blocks: Option<usize>,
+    candidates: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            method: KeySizeMethod::Hamming,
+            key_sizes: 2..=40,
+            blocks: None,
+            candidates: 4,
+        }
+    }
+}
+
+impl Config {
+    #[must_use]
+    pub const fn method(mut self, method: KeySizeMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// The range of the key sizes to try
+    #[must_use]
+    pub const fn key_sizes(mut self, key_sizes: RangeInclusive<usize>) -> Self {
+        self.key_sizes = key_sizes;
+        self
+    }
+
+    /// Compare only the given number of the first blocks in the [`KeySizeMethod::Hamming`].
+    /// All the blocks are used by default, which is more reliable on the short ciphertexts.
+    /// At least two blocks are always compared.
+    #[must_use]
+    pub const fn blocks(mut self, blocks: usize) -> Self {
+        self.blocks = Some(if blocks < 2 { 2 } else { blocks });
+        self
+    }
+
+    /// The number of the best key sizes to try breaking
+    #[must_use]
+    pub const fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates;
+        self
+    }
+}
+
+/// Score every key size in the configured range,
+/// the most probable sizes first.
+///
+/// The sizes too large to analyze the data are skipped.
+pub fn estimate_key_size(data: &[u8], config: &Config) -> Vec<(usize, f64)> {
+    let sizes = config
+        .key_sizes
+        .clone()
+        .filter(|&key_size| key_size > 0 && data.len() >= key_size * 2);
+
+    match config.method {
+        KeySizeMethod::Hamming => {
+            let mut scores: Vec<_> = sizes
+                .map(|key_size| (key_size, hamming_distance(data, key_size, config.blocks)))
+                .collect();
+            scores.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+            scores
+        }
+        KeySizeMethod::IndexOfCoincidence => {
+            let mut scores: Vec<_> = sizes
+                .map(|key_size| {
+                    let columns = transpose(data, key_size);
+                    #[allow(clippy::cast_precision_loss)]
+                    let average = columns
+                        .iter()
+                        .map(|column| index_of_coincidence(column))
+                        .sum::<f64>()
+                        / key_size as f64;
+                    (key_size, average)
+                })
+                .collect();
+            scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            scores
+        }
+        KeySizeMethod::Friedman => {
+            let estimate = friedman_key_size(data);
+            #[allow(clippy::cast_precision_loss)]
+            let mut scores: Vec<_> = sizes
+                .map(|key_size| (key_size, (key_size as f64 - estimate).abs()))
+                .collect();
+            scores.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+            scores
+        }
+    }
+}
+
+fn hamming_distance(data: &[u8], key_size: usize, blocks: Option<usize>) -> f64 {
+    #![allow(clippy::cast_precision_loss)]
+
+    let blocks: Vec<_> = data
+        .chunks_exact(key_size)
+        .take(blocks.unwrap_or(usize::MAX))
+        .collect();
+
+    let pairs = blocks.len().saturating_sub(1).max(1);
+    let total_distance: u32 = blocks[1..]
+        .iter()
+        .zip(&blocks)
+        .map(|(block1, block2)| hamming(block1, block2))
+        .sum();
+
+    // normalized
+    f64::from(total_distance) / (pairs * key_size) as f64
+}
+
+fn transpose(data: &[u8], key_size: usize) -> Vec<Vec<u8>> {
+    (0..key_size)
+        .map(|index| data.iter().skip(index).step_by(key_size).copied().collect())
+        .collect()
+}
+
+/// The chance that two bytes taken at random are the same
+fn index_of_coincidence(data: &[u8]) -> f64 {
+    #![allow(clippy::cast_precision_loss)]
+
+    if data.len() < 2 {
+        return 0.0;
+    }
+
+    let mut counts = [0_usize; 256];
+    for &byte in data {
+        counts[usize::from(byte)] += 1;
+    }
+
+    let coincidences: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    coincidences as f64 / (data.len() * (data.len() - 1)) as f64
+}
+
+/// Friedman test: the key size where the expected index of coincidence
+/// of the ciphertext matches the real one
+fn friedman_key_size(data: &[u8]) -> f64 {
+    let kappa = index_of_coincidence(data);
+    if kappa <= RANDOM_KAPPA {
+        return f64::INFINITY;
+    }
+    (ENGLISH_KAPPA - RANDOM_KAPPA) / (kappa - RANDOM_KAPPA)
+}
+
+/// Break every column of the given key size with a single byte
+/// expecting the printable ASCII text
+///
+/// # Errors
+/// Some column cannot be decrypted into the printable text
+pub fn find_key<S>(data: &[u8], key_size: usize, scorer: &S) -> Result<Vec<u8>>
+where
+    S: Scorer + ?Sized,
+{
+    find_key_with(data, key_size, &AsciiText::new(scorer))
+}
+
+/// Break every column of the given key size with a single byte
+/// scoring the columns with the model
+///
+/// # Errors
+/// The model rejects every decryption of some column
+pub fn find_key_with<M>(data: &[u8], key_size: usize, model: &M) -> Result<Vec<u8>>
+where
+    M: PlaintextModel + ?Sized,
+{
+    transpose(data, key_size)
+        .into_iter()
+        .map(|column| column.find_key_byte(model))
+        .collect()
+}
+
+/// The recovered key along with its ranks
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyCandidate {
+    pub key: Vec<u8>,
+    /// The score of the key size by the configured method
+    pub key_size_score: f64,
+    /// The score of the decrypted data, the lower the better
+    pub text_score: u64,
+}
+
+/// Try the best key sizes and return the keys decrypting the data
+/// into the most English-like text first.
+pub fn break_repeating_key<S>(data: &[u8], config: &Config, scorer: &S) -> Vec<KeyCandidate>
+where
+    S: Scorer + ?Sized,
+{
+    let mut candidates: Vec<_> = estimate_key_size(data, config)
+        .into_iter()
+        .take(config.candidates)
+        .filter_map(|(key_size, key_size_score)| {
+            debug!(
+                target: logging::ATTACK,
+                "Breaking the key of size {} (score={:.3})", key_size, key_size_score
+            );
+            let key = find_key(data, key_size, scorer).ok()?;
+            let plain = String::from_utf8(data.xor_ref(key.iter())).ok()?;
+            Some(KeyCandidate {
+                key,
+                key_size_score,
+                text_score: scorer.score(&plain),
+            })
+        })
+        .collect();
+
+    candidates.sort_by_key(|candidate| candidate.text_score);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::LetterFrequency;
+
+    use super::*;
+
+    const PLAIN: &str = "It was the best of times, it was the worst of times, \
+        it was the age of wisdom, it was the age of foolishness, \
+        it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of Light, it was the season of Darkness, \
+        it was the spring of hope, it was the winter of despair, \
+        we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way - \
+        in short, the period was so far like the present period, \
+        that some of its noisiest authorities insisted on its being received, \
+        for good or for evil, in the superlative degree of comparison only. \
+        There were a king with a large jaw and a queen with a plain face, \
+        on the throne of England; there were a king with a large jaw \
+        and a queen with a fair face, on the throne of France. \
+        In both countries it was clearer than crystal to the lords \
+        of the State preserves of loaves and fishes, \
+        that things in general were settled for ever.";
+
+    const KEY: &str = "Terminator X: Bring the noise";
+
+    fn encrypted(key: &str) -> Vec<u8> {
+        PLAIN.xor(key.bytes())
+    }
+
+    #[test]
+    fn hamming_key_size() {
+        let data = encrypted(KEY);
+        let scores = estimate_key_size(&data, &Config::default());
+        assert_eq!(scores[0].0, KEY.len());
+        assert!(scores.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+
+    #[test]
+    fn at_least_two_blocks_are_compared() {
+        let data = encrypted(KEY);
+        let two_blocks = estimate_key_size(&data, &Config::default().blocks(2));
+        assert!(two_blocks.iter().all(|(_, distance)| *distance > 0.0));
+
+        for blocks in 0..2 {
+            let config = Config::default().blocks(blocks);
+            assert_eq!(estimate_key_size(&data, &config), two_blocks);
+        }
+    }
+
+    #[test]
+    fn index_of_coincidence_key_size() {
+        let data = encrypted(KEY);
+        let config = Config::default().method(KeySizeMethod::IndexOfCoincidence);
+        let scores = estimate_key_size(&data, &config);
+        assert_eq!(scores[0].0, KEY.len());
+        assert!(scores.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn friedman_key_size_of_the_short_key() {
+        let data = encrypted("ICE");
+        let config = Config::default().method(KeySizeMethod::Friedman);
+        let scores = estimate_key_size(&data, &config);
+        assert!(
+            scores.iter().take(2).any(|(key_size, _)| *key_size == 3),
+            "{:?}",
+            scores
+        );
+    }
+
+    #[test]
+    fn key_sizes_range() {
+        let data = encrypted(KEY);
+        let config = Config::default().key_sizes(5..=10);
+        let sizes: Vec<_> = estimate_key_size(&data, &config)
+            .into_iter()
+            .map(|(key_size, _)| key_size)
+            .collect();
+        assert_eq!(sizes.len(), 6);
+        assert!(sizes.iter().all(|key_size| (5..=10).contains(key_size)));
+
+        // too large to compare even two blocks
+        let config = Config::default().key_sizes(1000..=1001);
+        assert!(estimate_key_size(&data, &config).is_empty());
+    }
+
+    #[test]
+    fn break_the_key() {
+        let data = encrypted(KEY);
+        let candidates = break_repeating_key(&data, &Config::default(), &LetterFrequency);
+        assert_eq!(candidates[0].key, KEY.as_bytes());
+        assert!(candidates
+            .windows(2)
+            .all(|pair| pair[0].text_score <= pair[1].text_score));
+    }
+
+    #[test]
+    fn break_the_binary_key() {
+        use crate::plaintext::ByteEntropy;
+
+        // mostly zeroes with some noise, like the executables
+        let plain: Vec<u8> = (0_u16..2000)
+            .map(|i| if i % 7 == 0 { (i % 251) as u8 } else { 0 })
+            .collect();
+        let key = b"\x01\xfe\x80ICE";
+        let data = plain.xor_ref(key.iter());
+
+        assert!(find_key(&data, key.len(), &LetterFrequency).is_err());
+        assert_eq!(
+            find_key_with(&data, key.len(), &ByteEntropy::default()).unwrap(),
+            key
+        );
+    }
+}
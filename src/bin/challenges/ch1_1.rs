@@ -0,0 +1,20 @@
+use std::error::Error;
+
+use base64::encode;
+use log::debug;
+use pals::StrCryptoExt;
+
+const HEX_REPR: &str = "49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    debug!("Hex representation: {}", HEX_REPR);
+
+    let raw = HEX_REPR.parse_hex()?;
+    debug!("Raw bytes representation: {:?}", &raw);
+
+    Ok(encode(&raw))
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("SSdt") && result.ends_with("2hyb29t")
+}
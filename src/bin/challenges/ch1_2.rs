@@ -0,0 +1,21 @@
+use std::error::Error;
+
+use log::debug;
+use pals::{HexDisplay, StrCryptoExt, StreamCipher};
+
+const A: &str = "1c0111001f010100061a024b53535009181c";
+const B: &str = "686974207468652062756c6c277320657965";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let raw1 = A.parse_hex()?;
+    let raw2 = B.parse_hex()?;
+
+    let data = raw1.xor(raw2.into_iter());
+    debug!("{:x?}", data);
+
+    Ok(data.as_hex())
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("7468") && result.ends_with("c6179")
+}
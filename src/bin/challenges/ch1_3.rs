@@ -0,0 +1,23 @@
+use std::error::Error;
+
+use log::info;
+use pals::{score::ByteFrequency, BytesCryptoExt, StrCryptoExt};
+
+const ENCODED: &str = "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let candidates = ENCODED
+        .parse_hex()?
+        .guess_the_single_char_xor_key(&ByteFrequency::default());
+    let (key, plain, score) = candidates.first().ok_or("No candidate")?;
+
+    info!(
+        "The key is {}({:?}). Plaintext is: {:?} (score={})",
+        key, *key as char, plain, score
+    );
+    Ok(format!("{}:{}", *key as char, plain))
+}
+
+pub fn check(result: &str) -> bool {
+    result == "X:Cooking MC's like a pound of bacon"
+}
@@ -0,0 +1,38 @@
+use std::error::Error;
+
+use log::{debug, info};
+use pals::{data, score::ByteFrequency, BytesCryptoExt, HexDisplay};
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let scorer = ByteFrequency::default();
+    let mut best = None;
+    for (i, line) in data::load_lines(4)?.iter().enumerate() {
+        // the scores per byte are comparable between the lines
+        let Some((key, plain, score)) = line
+            .guess_the_single_char_xor_key(&scorer)
+            .into_iter()
+            .next()
+        else {
+            continue;
+        };
+
+        debug!("{}-th LINE: {:?} (score={})", i, line.as_hex(), score);
+        if best
+            .as_ref()
+            .map_or(true, |(_, _, best_score)| score < *best_score)
+        {
+            best = Some((key, plain, score));
+        }
+    }
+
+    let (key, plain, score) = best.ok_or("No line is encrypted with a single character")?;
+    info!(
+        "The key is {}({:?}). Plaintext is: {:?} (score={})",
+        key, key as char, plain, score
+    );
+    Ok(format!("{}:{}", key as char, plain))
+}
+
+pub fn check(result: &str) -> bool {
+    result == "5:Now that the party is jumping\n"
+}
@@ -0,0 +1,19 @@
+use std::error::Error;
+
+use pals::{HexDisplay, StreamCipher};
+
+const PLAIN: &str = r#"Burning 'em, if you ain't quick and nimble
+I go crazy when I hear a cymbal"#;
+
+const KEY: &str = "ICE";
+
+/// To encrypt any other data with the same key, use the `pals xor --key ICE`
+#[allow(clippy::unnecessary_wraps)]
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let ciphered = PLAIN.xor(KEY.bytes());
+    Ok(ciphered.as_hex())
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("0b3637272") && result.trim_end().ends_with("2e27282f")
+}
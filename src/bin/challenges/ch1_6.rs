@@ -0,0 +1,35 @@
+use std::error::Error;
+
+use log::info;
+use pals::{
+    attacks::xor::{break_repeating_key, Config},
+    data,
+    score::LetterFrequency,
+    StreamCipher,
+};
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let data = data::load(6)?;
+
+    let candidates = break_repeating_key(&data, &Config::default(), &LetterFrequency);
+    for candidate in &candidates {
+        info!(
+            "Found a key: {:?} (key size score={:.3}, text score={})",
+            String::from_utf8_lossy(&candidate.key),
+            candidate.key_size_score,
+            candidate.text_score
+        );
+    }
+
+    let best = candidates.first().ok_or("No key found")?;
+    let key_str = String::from_utf8(best.key.clone())?;
+    let data = String::from_utf8(data.xor_ref(best.key.iter()))?;
+    Ok(format!("{}\n{}", key_str, data))
+}
+
+pub fn check(result: &str) -> bool {
+    let (key, text) = result.split_once('\n').unwrap_or_default();
+    key == "Terminator X: Bring the noise"
+        && text.starts_with("I'm back and I'm ringin' the bell")
+        && text.trim_end().ends_with("Play that funky music")
+}
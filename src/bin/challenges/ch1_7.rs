@@ -0,0 +1,20 @@
+use std::error::Error;
+
+use pals::{
+    aes_cypher::{BlockMode, Builder},
+    data,
+};
+
+const KEY: &str = "YELLOW SUBMARINE";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let data = data::load(7)?;
+
+    let ecb = Builder::new(KEY.as_bytes())?.ecb();
+    Ok(String::from_utf8(ecb.decrypt(&data)?)?)
+}
+
+pub fn check(text: &str) -> bool {
+    text.starts_with("I'm back and I'm ringin' the bell")
+        && text.trim_end().ends_with("Play that funky music")
+}
@@ -0,0 +1,49 @@
+use std::{collections::HashMap, error::Error};
+
+use log::debug;
+use pals::{
+    aes_cypher::{BlockMode, Builder, Padding},
+    data, HexDisplay,
+};
+
+const RANDOM_KEY: &str = "YELLOW SUBMARINE";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    for (i, text) in data::load_lines(8)?.into_iter().enumerate() {
+        let same_results = try_decrypt(&text, RANDOM_KEY.as_bytes())?;
+
+        if let Some(true) = same_results {
+            debug!(
+                "{}-th Line {:?} has been ciphered in ECB mode for sure",
+                i,
+                text.as_hex()
+            );
+            return Ok(format!("{}:{}", i, text.as_hex()));
+        }
+    }
+
+    Err("No line is encrypted in ECB mode".into())
+}
+
+fn try_decrypt(data: &[u8], key: &[u8]) -> pals::Result<Option<bool>> {
+    let mut processed_pairs = HashMap::new();
+    let ecb = Builder::new(key)?.padding(Padding::NoPadding).ecb();
+    let decrypted = ecb.decrypt(data)?;
+
+    for (block_number, (ciphered, deciphered)) in
+        data.chunks(16).zip(decrypted.chunks(16)).enumerate()
+    {
+        if let Some(before) = processed_pairs.get(&ciphered) {
+            debug!("{}.{:?}", block_number, ciphered.as_hex());
+            return Ok(Some(before == &deciphered));
+        }
+
+        processed_pairs.insert(ciphered, deciphered);
+    }
+
+    Ok(None)
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("132:d880619740")
+}
@@ -0,0 +1,21 @@
+use std::error::Error;
+
+use pals::{
+    aes_cypher::{BlockMode, Builder},
+    data,
+};
+
+const KEY: &str = "YELLOW SUBMARINE";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let iv = vec![0; 16];
+    let data = data::load(10)?;
+
+    let cbc = Builder::new(KEY.as_bytes())?.iv(&iv)?.cbc();
+    Ok(String::from_utf8(cbc.decrypt(&data)?)?)
+}
+
+pub fn check(text: &str) -> bool {
+    text.starts_with("I'm back and I'm ringin' the bell")
+        && text.trim_end().ends_with("Play that funky music")
+}
@@ -0,0 +1,130 @@
+use std::error::Error;
+
+use log::debug;
+use rand::Rng;
+
+use pals::{
+    aes_cypher::{BlockMode, Builder, CounterLayout},
+    random, BytesCryptoExt,
+};
+
+const ROUNDS: usize = 100;
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let mut detected = 0;
+    for i in 0..ROUNDS {
+        debug!("{}. ========================", i);
+        if detect_mode()? {
+            detected += 1;
+        }
+    }
+
+    Ok(format!("{}/{}", detected, ROUNDS))
+}
+
+pub fn check(result: &str) -> bool {
+    result == format!("{}/{}", ROUNDS, ROUNDS)
+}
+
+fn detect_mode() -> pals::Result<bool> {
+    // at least 4 blocks of data should be used, to allow random bytes padding
+    // to eat some data from the beginning (first block) and the end (last block).
+    // The size is chosen such that with 5..=10 random bytes added from both sides
+    // the total size (81..=91) is never aligned to the block,
+    // so only the modes with padding can produce whole blocks.
+    let data = vec![0; 71];
+
+    let (enc, hidden_mode) = encrypt_random(&data)?;
+    debug!("Encrypted data is: {:?}", enc);
+
+    // take the second and third blocks
+    let blocks: Vec<_> = enc.chunks(16).skip(1).take(2).collect();
+
+    let detected_family = if enc.len() % 16 != 0 {
+        debug!("The encryption was made with some streaming mode!");
+        ModeFamily::Stream
+    } else if blocks[0] == blocks[1] {
+        debug!("The encryption was made with the EBC mode!");
+        ModeFamily::Ebc
+    } else {
+        debug!("It is definitely NOT the EBC mode!");
+        ModeFamily::Chaining
+    };
+
+    Ok(hidden_mode.family() == detected_family)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Ebc,
+    Cbc,
+    Pcbc,
+    Cfb8,
+    Cfb128,
+    Ofb,
+    Ctr,
+}
+
+/// The modes which are indistinguishable by a single ciphertext
+#[derive(Debug, PartialEq)]
+enum ModeFamily {
+    Ebc,
+    /// Padded modes mixing the blocks together
+    Chaining,
+    /// Modes producing the ciphertext of the same size as the plaintext
+    Stream,
+}
+
+impl Mode {
+    const ALL: [Self; 7] = [
+        Self::Ebc,
+        Self::Cbc,
+        Self::Pcbc,
+        Self::Cfb8,
+        Self::Cfb128,
+        Self::Ofb,
+        Self::Ctr,
+    ];
+
+    const fn family(self) -> ModeFamily {
+        match self {
+            Self::Ebc => ModeFamily::Ebc,
+            Self::Cbc | Self::Pcbc => ModeFamily::Chaining,
+            Self::Cfb8 | Self::Cfb128 | Self::Ofb | Self::Ctr => ModeFamily::Stream,
+        }
+    }
+}
+
+fn encrypt_random(data: &[u8]) -> pals::Result<(Vec<u8>, Mode)> {
+    let mut rng = random::rng();
+    let bytes_before = rng.gen_range(5, 11);
+    let bytes_after = rng.gen_range(5, 11);
+
+    debug!("Original data to encrypt: {:?}", data);
+    let mut salted = Vec::generate_random(bytes_before);
+    salted.extend_from_slice(data);
+    salted.extend(Vec::generate_random(bytes_after));
+    debug!(
+        "Add {} bytes to beginning and {} bytes to the end: {:?}",
+        bytes_before, bytes_after, salted
+    );
+
+    let key = Vec::generate_random(16);
+    debug!("Random key to encrypt: {:?}", key);
+    let iv = Vec::generate_random(16);
+    let builder = Builder::new(&key)?.iv(&iv)?;
+
+    let mode = Mode::ALL[rng.gen_range(0, Mode::ALL.len())];
+    debug!("Encrypting in the {:?} mode...", mode);
+    let cipher: Box<dyn BlockMode> = match mode {
+        Mode::Ebc => Box::new(builder.ecb()),
+        Mode::Cbc => Box::new(builder.cbc()),
+        Mode::Pcbc => Box::new(builder.pcbc()),
+        Mode::Cfb8 => Box::new(builder.cfb8()),
+        Mode::Cfb128 => Box::new(builder.cfb128()),
+        Mode::Ofb => Box::new(builder.ofb()),
+        Mode::Ctr => Box::new(builder.ctr(CounterLayout::LittleEndian { nonce: rng.gen() })),
+    };
+
+    Ok((cipher.encrypt(&salted)?, mode))
+}
@@ -0,0 +1,65 @@
+use std::error::Error;
+
+use log::{debug, info};
+use pals::{attacks::ecb::EcbBreaking, oracle::Metered};
+
+mod blackbox {
+    use lazy_static::lazy_static;
+
+    use pals::{
+        aes_cypher::{BlockMode, Builder, Ecb},
+        encoding::Encoding,
+        BytesCryptoExt,
+    };
+
+    lazy_static! {
+        static ref KEY: Vec<u8> = Vec::generate_random(16);
+        static ref CIPHER: Ecb = Builder::new(&KEY).unwrap().ecb();
+    }
+
+    const UNKNOWN_PLAINTEXT: &str = r#"
+Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkg
+aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq
+dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg
+YnkK"#;
+
+    pub fn encrypt(data: &[u8]) -> Vec<u8> {
+        // eprintln!("Original data to encrypt: {:?}", data);
+
+        let suffix = Encoding::Base64.decode(UNKNOWN_PLAINTEXT).unwrap();
+        // eprintln!("Unknown suffix is {:?}", suffix);
+
+        let data: Vec<_> = data.iter().copied().chain(suffix).collect();
+        // eprintln!(
+        //     "Final data to encrypt with the key {:?}: {:?} (size={})",
+        //     KEY.as_slice(), data, data.len());
+        CIPHER
+            .encrypt(&data)
+            .expect("Padding always produces whole blocks")
+    }
+}
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    // 1. Discover the block size of the cipher. You know it, but do this step anyway.
+    // 2. Detect that the function is using ECB. You already know, but do this step anyways.
+    // 3. Reveal the unknown suffix byte by byte.
+    let ecb = EcbBreaking::new(Metered::new(blackbox::encrypt))?;
+    let recovered = ecb.recover()?;
+    info!("Revealed with {} oracle queries", ecb.oracle().queries());
+    debug!(
+        "The block size is {}. Suffix size is {}",
+        recovered.block_size, recovered.suffix_len
+    );
+    if recovered.prefix_len != 0 {
+        return Err(format!("Unexpected prefix of {} bytes", recovered.prefix_len).into());
+    }
+
+    Ok(String::from_utf8(recovered.suffix)?)
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("Rollin' in my 5.0")
+        && result
+            .trim_end()
+            .ends_with("Did you stop? No, I just drove by")
+}
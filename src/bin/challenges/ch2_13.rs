@@ -1,3 +1,7 @@
+use std::error::Error;
+
+use log::{debug, info};
+
 use self::blackbox::Profile;
 use pals::BytesCryptoExt;
 
@@ -6,15 +10,20 @@ mod blackbox {
 
     use itertools::Itertools;
     use lazy_static::lazy_static;
+    use log::debug;
 
-    use pals::{aes_cypher, BytesCryptoExt};
+    use pals::{
+        aes_cypher::{BlockMode, Builder, Ecb},
+        BytesCryptoExt,
+    };
 
     lazy_static! {
         static ref KEY: Vec<u8> = Vec::generate_random(16);
+        static ref CIPHER: Ecb = Builder::new(&KEY).unwrap().ecb();
     }
 
     fn get_profile(email: &str) -> Vec<(&str, String)> {
-        let email = email.replace(|ch| (ch == '&') || (ch == '='), "");
+        let email = email.replace(&['&', '='][..], "");
         vec![
             ("email", email),
             ("uid", "10".into()),
@@ -92,17 +101,18 @@ mod blackbox {
 
     pub fn profile_for(email: &str) -> Vec<u8> {
         let x = fmt_pairs(&get_profile(email));
-        aes_cypher::encrypt(x.bytes().collect(), &KEY).concat()
+        CIPHER
+            .encrypt(x.as_bytes())
+            .expect("Padding always produces whole blocks")
     }
 
     pub fn decrypt_profile(profile_enc: &[u8]) -> Option<Profile> {
-        let mut profile_bytes = aes_cypher::decrypt(profile_enc, &KEY).concat();
-        eprintln!(
+        let profile_bytes = CIPHER.decrypt(profile_enc).ok()?;
+        debug!(
             "Profile bytes: {:?}",
             String::from_utf8(profile_bytes.clone())
         );
-        profile_bytes.unpad_pkcs7(16);
-        Profile::from_cookie(&String::from_utf8(profile_bytes).unwrap())
+        Profile::from_cookie(&String::from_utf8(profile_bytes).ok()?)
     }
 
     #[derive(Debug)]
@@ -126,13 +136,17 @@ mod blackbox {
             &self.email
         }
 
+        pub fn uid(&self) -> &str {
+            &self.uid
+        }
+
         pub fn role(&self) -> &str {
             &self.role
         }
     }
 }
 
-fn elevate_privileges() -> Profile {
+fn elevate_privileges() -> Option<Profile> {
     // Craft the first block
     // "email=xxxx@acme."
     // <---------------->
@@ -161,30 +175,34 @@ fn elevate_privileges() -> Profile {
     let email_real_suffix_fill_in = block_size - mandatory_structure.len();
     let email_real_suffix = "c".repeat(email_real_suffix_fill_in);
 
-    let email = vec![
+    let email = [
         email_username,
         email_suffix_in_block.to_string(),
         role_str,
         email_real_suffix,
     ]
     .concat();
-    eprintln!("Full email with injected payload: {:?}", email);
+    debug!("Full email with injected payload: {:?}", email);
     let enc_profile = blackbox::profile_for(&email);
 
     let blocks: Vec<_> = enc_profile.chunks(16).collect();
-    let admin_profile_enc = vec![blocks[0], blocks[2], blocks[1]].concat();
-    blackbox::decrypt_profile(&admin_profile_enc).unwrap()
+    let admin_profile_enc = [blocks[0], blocks[2], blocks[1]].concat();
+    blackbox::decrypt_profile(&admin_profile_enc)
 }
 
-fn main() {
-    let profile = elevate_privileges();
-    println!("Generated profile: {:?}", profile);
-    assert_result(&profile);
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let profile = elevate_privileges().ok_or("The forged profile is invalid")?;
+    info!("Generated profile: {:?}", profile);
+    Ok(format!(
+        "email={}&uid={}&role={}",
+        profile.email(),
+        profile.uid(),
+        profile.role()
+    ))
 }
 
-fn assert_result(profile: &Profile) {
-    assert_eq!(profile.email(), "xxxx@acme.ccc");
-    assert_eq!(profile.role(), "admin");
+pub fn check(result: &str) -> bool {
+    result == "email=xxxx@acme.ccc&uid=10&role=admin"
 }
 
 #[cfg(test)]
@@ -0,0 +1,89 @@
+use std::error::Error;
+
+use log::{debug, info};
+use pals::{attacks::ecb::EcbBreaking, oracle::Metered};
+
+mod blackbox {
+    use std::sync::Mutex;
+
+    use lazy_static::lazy_static;
+    use rand::Rng;
+
+    use pals::{
+        aes_cypher::{BlockMode, Builder, Ecb},
+        encoding::Encoding,
+        random, BytesCryptoExt,
+    };
+
+    lazy_static! {
+        static ref KEY: Vec<u8> = Vec::generate_random(16);
+        static ref CIPHER: Ecb = Builder::new(&KEY).unwrap().ecb();
+        static ref RANDOM_PREFIX: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+    }
+
+    fn get_prefix() -> Vec<u8> {
+        let mut guard = RANDOM_PREFIX.lock().unwrap();
+
+        if let Some(prefix) = &*guard {
+            return prefix.clone();
+        }
+
+        let mut rng = random::rng();
+        let prefix_size = rng.gen_range(100, 1000);
+        let result = Vec::generate_random(prefix_size);
+        *guard = Some(result.clone());
+        result
+    }
+
+    const UNKNOWN_PLAINTEXT: &str = r#"
+Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkg
+aGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBq
+dXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUg
+YnkK"#;
+
+    fn get_suffix() -> Vec<u8> {
+        Encoding::Base64.decode(UNKNOWN_PLAINTEXT).unwrap()
+    }
+
+    pub fn encrypt(data: &[u8]) -> Vec<u8> {
+        let prefix = get_prefix();
+        let suffix = get_suffix();
+
+        // eprintln!("Original data to encrypt: {:?}", data);
+        // eprintln!(
+        //     "Lengths of: prefix: {}, data: {}, suffix: {}",
+        //     prefix.len(), data.len(), suffix.len());
+
+        let data: Vec<_> = prefix
+            .into_iter()
+            .chain(data.to_vec())
+            .chain(suffix)
+            .collect();
+
+        // eprintln!(
+        //     "Final data to encrypt with the key {:?}: {:?} (size={})",
+        //     KEY.as_slice(), data, data.len());
+        CIPHER
+            .encrypt(&data)
+            .expect("Padding always produces whole blocks")
+    }
+}
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let ecb = EcbBreaking::new(Metered::new(blackbox::encrypt))?;
+    let recovered = ecb.recover()?;
+    info!("Revealed with {} oracle queries", ecb.oracle().queries());
+    debug!(
+        "The block size is {}. Prefix size is {}. Suffix size is {}",
+        recovered.block_size, recovered.prefix_len, recovered.suffix_len
+    );
+
+    Ok(String::from_utf8(recovered.suffix)?)
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("Rollin' in my 5.0")
+        && result
+            .trim_end()
+            .ends_with("Did you stop? No, I just drove by")
+}
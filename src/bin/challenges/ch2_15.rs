@@ -0,0 +1,22 @@
+use std::error::Error;
+
+use pals::StrCryptoExt;
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let s = "ICE ICE BABY\u{04}\u{04}\u{04}\u{04}";
+    let stripped = s.strip_pkcs7_padding(16)?;
+
+    let bad = [
+        "ICE ICE BABY\u{05}\u{05}\u{05}\u{05}",
+        "ICE ICE BABY\u{01}\u{02}\u{03}\u{04}",
+    ];
+    if let Some(accepted) = bad.iter().find(|s| s.strip_pkcs7_padding(16).is_ok()) {
+        return Err(format!("The bad padding is accepted: {:?}", accepted).into());
+    }
+
+    Ok(stripped)
+}
+
+pub fn check(result: &str) -> bool {
+    result == "ICE ICE BABY"
+}
@@ -1,20 +1,32 @@
+use std::error::Error;
+
+use log::info;
+
 mod blackbox {
-    use itertools::Itertools;
     use lazy_static::lazy_static;
+    use log::debug;
 
-    use pals::{aes_cypher, BytesCryptoExt};
+    use pals::{
+        aes_cypher::{BlockMode, Builder, Cbc},
+        encoding::Encoding,
+        BytesCryptoExt,
+    };
 
     lazy_static! {
         static ref KEY: Vec<u8> = Vec::generate_random(16);
         static ref IV: Vec<u8> = Vec::generate_random(16);
+        static ref CIPHER: Cbc = Builder::new(&KEY).unwrap().iv(&IV).unwrap().cbc();
     }
 
     fn get_entry(input: &str) -> String {
-        let input = input.replace(|ch| (ch == ';') || (ch == '='), "");
+        let input = input.replace(&[';', '='][..], "");
         [
-            "comment1=cooking%20MCs;userdata=",
+            "comment1=",
+            &Encoding::Percent.encode(b"cooking MCs"),
+            ";userdata=",
             &input,
-            ";comment2=%20like%20a%20pound%20of%20bacon",
+            ";comment2=",
+            &Encoding::Percent.encode(b" like a pound of bacon"),
         ]
         .concat()
     }
@@ -30,18 +42,19 @@ mod blackbox {
             .collect()
     }
 
-    pub fn entry_for(data: &str) -> Vec<Vec<u8>> {
+    pub fn entry_for(data: &str) -> Vec<u8> {
         let entry = get_entry(data);
-        aes_cypher::encrypt_cbc(entry.into_bytes(), &KEY, IV.clone())
+        CIPHER
+            .encrypt(entry.as_bytes())
+            .expect("Padding always produces whole blocks")
     }
 
     pub fn decrypt_entry(profile_enc: &[u8]) -> Option<String> {
-        let mut entry_bytes = aes_cypher::decrypt_cbc(profile_enc, &KEY, IV.clone()).concat();
-        entry_bytes.unpad_pkcs7(16);
+        let entry_bytes = CIPHER.decrypt(profile_enc).ok()?;
 
         // TODO: check for valid UTF-8
         let entry = String::from_utf8_lossy(&entry_bytes);
-        eprintln!("Entry bytes: {:?}", entry);
+        debug!("Entry bytes: {:?}", entry);
 
         parse_entry(&entry)
             .iter()
@@ -57,17 +70,21 @@ fn elevate_privileges() -> Option<String> {
     let mut entry = blackbox::entry_for(target_block);
     // scramble the second (insignificant) block and force
     // the bitflipping in the third (target) block
-    if let Some(second_block) = entry.get_mut(1) {
+    if let Some(second_block) = entry.chunks_mut(16).nth(1) {
         second_block[5] ^= 4;
         second_block[11] ^= 2;
     }
     // let entry = &entry[1..];
 
-    blackbox::decrypt_entry(&entry.concat())
+    blackbox::decrypt_entry(&entry)
 }
 
-fn main() {
+pub fn solve() -> Result<String, Box<dyn Error>> {
     let is_admin_role = elevate_privileges();
-    println!("Is admin role: {:?}", is_admin_role);
-    assert_eq!(is_admin_role.unwrap(), "true");
+    info!("Is admin role: {:?}", is_admin_role);
+    Ok(is_admin_role.ok_or("No admin field")?)
+}
+
+pub fn check(result: &str) -> bool {
+    result == "true"
 }
@@ -0,0 +1,16 @@
+use std::error::Error;
+
+use pals::BytesCryptoExt;
+
+const PLAIN_TEXT: &str = "YELLOW SUBMARINE";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let mut data: Vec<_> = PLAIN_TEXT.bytes().collect();
+    data.pad_pkcs7(20);
+
+    Ok(String::from_utf8(data)?)
+}
+
+pub fn check(result: &str) -> bool {
+    result == "YELLOW SUBMARINE\u{4}\u{4}\u{4}\u{4}"
+}
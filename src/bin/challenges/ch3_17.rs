@@ -0,0 +1,53 @@
+use std::error::Error;
+
+use log::{debug, info};
+use pals::{
+    attacks::padding_oracle::{self, Service},
+    encoding::Encoding,
+    oracle::Metered,
+};
+
+const SECRETS: [&str; 10] = [
+    "MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
+    "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
+    "MDAwMDAyUXVpY2sgdG8gdGhlIHBvaW50LCB0byB0aGUgcG9pbnQsIG5vIGZha2luZw==",
+    "MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg==",
+    "MDAwMDA0QnVybmluZyAnZW0sIGlmIHlvdSBhaW4ndCBxdWljayBhbmQgbmltYmxl",
+    "MDAwMDA1SSBnbyBjcmF6eSB3aGVuIEkgaGVhciBhIGN5bWJhbA==",
+    "MDAwMDA2QW5kIGEgaGlnaCBoYXQgd2l0aCBhIHNvdXBlZCB1cCB0ZW1wbw==",
+    "MDAwMDA3SSdtIG9uIGEgcm9sbCwgaXQncyB0aW1lIHRvIGdvIHNvbG8=",
+    "MDAwMDA4b2xsaW4nIGluIG15IGZpdmUgcG9pbnQgb2g=",
+    "MDAwMDA5aXRoIG15IHJhZy10b3AgZG93biBzbyBteSBoYWlyIGNhbiBibG93",
+];
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let secrets = SECRETS
+        .iter()
+        .map(|secret| Encoding::Base64.decode(secret))
+        .collect::<Result<Vec<_>, _>>()?;
+    let service = Metered::new(Service::new(secrets));
+
+    let mut lines = Vec::new();
+    for i in 0..SECRETS.len() {
+        let (iv, cipher_text) = service.inner().encrypt_random();
+        let queries_before = service.queries();
+
+        let plain = padding_oracle::decrypt(&service, &iv, &cipher_text)?;
+        let plain = String::from_utf8(plain)?;
+        debug!("{}. {}", i, plain);
+        info!(
+            "Revealed with {} oracle queries",
+            service.queries() - queries_before
+        );
+        lines.push(plain);
+    }
+
+    Ok(lines.join("\n"))
+}
+
+pub fn check(result: &str) -> bool {
+    result.lines().count() == SECRETS.len()
+        && result
+            .lines()
+            .all(|line| line.starts_with("00000") && line.len() > 26)
+}
@@ -0,0 +1,21 @@
+use std::error::Error;
+
+use pals::{
+    aes_cypher::{Builder, CounterLayout},
+    StreamCipher,
+};
+
+const KEY: &str = "YELLOW SUBMARINE";
+const CIPHER_TEXT: &str =
+    "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==";
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let data = base64::decode(CIPHER_TEXT)?;
+
+    let ctr = Builder::new(KEY.as_bytes())?.ctr(CounterLayout::LittleEndian { nonce: 0 });
+    Ok(String::from_utf8(data.xor(ctr.keystream()))?)
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with("Yo, VIP Let's kick it") && result.trim_end().ends_with("Ice, Ice, baby")
+}
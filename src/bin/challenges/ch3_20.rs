@@ -0,0 +1,43 @@
+use std::error::Error;
+
+use log::{debug, info};
+use pals::{
+    aes_cypher::{BlockMode, Builder, CounterLayout},
+    attacks::fixed_nonce,
+    data,
+    score::ByteFrequency,
+    BytesCryptoExt, StrCryptoExt,
+};
+
+pub fn solve() -> Result<String, Box<dyn Error>> {
+    let key = Vec::generate_random(16);
+    let ctr = Builder::new(&key)?.ctr(CounterLayout::LittleEndian { nonce: 0 });
+    let cipher_texts = data::load_lines(20)?
+        .iter()
+        .map(|line| ctr.encrypt(line))
+        .collect::<pals::Result<Vec<_>>>()?;
+
+    let mut estimate = fixed_nonce::break_fixed_nonce(&cipher_texts, &ByteFrequency::default())?;
+    info!("Recovered {} bytes of the keystream", estimate.len());
+    for position in estimate.least_confident().into_iter().take(5) {
+        debug!(
+            "Not so sure about the byte {}: {:?}",
+            position,
+            estimate.bytes()[position]
+        );
+    }
+
+    // every line starts with the capital letter while the lowercase ones
+    // are much more frequent in the English text, so help it a bit
+    estimate.correct_plain(&cipher_texts[0], 0, b'I')?;
+
+    let plain_texts = cipher_texts
+        .iter()
+        .map(|cipher_text| String::from_utf8(estimate.decrypt(cipher_text)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(plain_texts.join("\n"))
+}
+
+pub fn check(result: &str) -> bool {
+    result.starts_with('I') && result.lines().all(StrCryptoExt::is_printable_ascii)
+}
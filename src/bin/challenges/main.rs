@@ -0,0 +1,165 @@
+//! Run the solutions of the challenges and check their answers:
+//!
+//! ```sh
+//! challenges [--set N | --challenge N] [--no-fixtures] [--json] [-v...]
+//! ```
+//!
+//! The input files are read with the [`pals::data`],
+//! set the `PALS_DATA_DIR` to take them from another directory.
+//! The process fails if any of the selected challenges fails.
+
+use std::{env, error::Error, process};
+
+mod registry;
+mod report;
+
+mod ch1_1;
+mod ch1_2;
+mod ch1_3;
+mod ch1_4;
+mod ch1_5;
+mod ch1_6;
+mod ch1_7;
+mod ch1_8;
+mod ch2_10;
+mod ch2_11;
+mod ch2_12;
+mod ch2_13;
+mod ch2_14;
+mod ch2_15;
+mod ch2_16;
+mod ch2_9;
+mod ch3_17;
+mod ch3_18;
+mod ch3_20;
+
+use pals::{logging, random};
+
+use self::registry::Selection;
+
+const USAGE: &str = "Usage: challenges [--set N | --challenge N] [--no-fixtures] [--json] [-v...]";
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    selection: Selection,
+    /// Skip the challenges with the input files
+    no_fixtures: bool,
+    json: bool,
+    /// The number of the `-v` flags
+    verbosity: usize,
+}
+
+fn parse_options<I>(args: I) -> Result<Options, Box<dyn Error>>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options {
+        selection: Selection::All,
+        no_fixtures: false,
+        json: false,
+        verbosity: 0,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("The {} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--set" => options.selection = Selection::Set(value()?.parse()?),
+            "--challenge" => options.selection = Selection::Challenge(value()?.parse()?),
+            "--no-fixtures" => options.no_fixtures = true,
+            "--json" => options.json = true,
+            flags if is_verbosity(flags) => options.verbosity += flags.len() - 1,
+            _ => return Err(format!("Unknown argument {:?}", arg).into()),
+        }
+    }
+
+    Ok(options)
+}
+
+/// The `-v`, `-vv`, etc
+fn is_verbosity(arg: &str) -> bool {
+    arg.strip_prefix('-')
+        .is_some_and(|flags| !flags.is_empty() && flags.bytes().all(|flag| flag == b'v'))
+}
+
+fn main() {
+    let options = parse_options(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    if let Err(err) = logging::init(logging::level(options.verbosity)) {
+        eprintln!("{}", err);
+    }
+    // fail early on the invalid seed, before the hook prints it
+    if let Err(err) = random::try_seed() {
+        eprintln!("{}", err);
+        process::exit(2);
+    }
+    random::install_panic_hook();
+
+    let reports: Vec<_> = options
+        .selection
+        .challenges()
+        .filter(|challenge| !(options.no_fixtures && challenge.fixture))
+        .map(report::run)
+        .collect();
+    if reports.is_empty() {
+        eprintln!("No challenge is selected");
+        process::exit(2);
+    }
+
+    if options.json {
+        print!("{}", report::json(&reports));
+    } else {
+        print!("{}", report::plain(&reports));
+    }
+
+    if !reports.iter().all(report::Report::passed) {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Options, Box<dyn Error>> {
+        parse_options(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn default_options() {
+        let options = parse("").unwrap();
+        assert_eq!(options.selection, Selection::All);
+        assert!(!options.no_fixtures);
+        assert!(!options.json);
+        assert_eq!(options.verbosity, 0);
+    }
+
+    #[test]
+    fn select_a_set() {
+        let options = parse("--set 2 --no-fixtures --json").unwrap();
+        assert_eq!(options.selection, Selection::Set(2));
+        assert!(options.no_fixtures);
+        assert_eq!(parse("-v --json -vv").unwrap().verbosity, 3);
+        assert!(options.json);
+
+        assert_eq!(
+            parse("--challenge 6").unwrap().selection,
+            Selection::Challenge(6)
+        );
+    }
+
+    #[test]
+    fn bad_options() {
+        assert!(parse("--set").is_err());
+        assert!(parse("--set two").is_err());
+        assert!(parse("--verbose").is_err());
+        assert!(parse("--data").is_err());
+        assert!(parse("-").is_err());
+        assert!(parse("-vx").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::error::Error;
+
+use super::{
+    ch1_1, ch1_2, ch1_3, ch1_4, ch1_5, ch1_6, ch1_7, ch1_8, ch2_10, ch2_11, ch2_12, ch2_13, ch2_14,
+    ch2_15, ch2_16, ch2_9, ch3_17, ch3_18, ch3_20,
+};
+
+pub type Solution = fn() -> Result<String, Box<dyn Error>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Challenge {
+    pub set: u8,
+    /// The number of the challenge is unique across all the sets
+    pub number: u8,
+    pub title: &'static str,
+    /// Whether the challenge reads the input file with the [`pals::data`]
+    pub fixture: bool,
+    pub solve: Solution,
+    /// Whether the answer is the expected one
+    pub check: fn(&str) -> bool,
+}
+
+macro_rules! challenge {
+    ($module:ident, $set:expr, $number:expr, $title:expr) => {
+        challenge!($module, $set, $number, $title, false)
+    };
+    ($module:ident, $set:expr, $number:expr, $title:expr, $fixture:expr) => {
+        Challenge {
+            set: $set,
+            number: $number,
+            title: $title,
+            fixture: $fixture,
+            solve: $module::solve,
+            check: $module::check,
+        }
+    };
+}
+
+pub const ALL: [Challenge; 19] = [
+    challenge!(ch1_1, 1, 1, "Convert hex to base64"),
+    challenge!(ch1_2, 1, 2, "Fixed XOR"),
+    challenge!(ch1_3, 1, 3, "Single-byte XOR cipher"),
+    challenge!(ch1_4, 1, 4, "Detect single-character XOR", true),
+    challenge!(ch1_5, 1, 5, "Implement repeating-key XOR"),
+    challenge!(ch1_6, 1, 6, "Break repeating-key XOR", true),
+    challenge!(ch1_7, 1, 7, "AES in ECB mode", true),
+    challenge!(ch1_8, 1, 8, "Detect AES in ECB mode", true),
+    challenge!(ch2_9, 2, 9, "Implement PKCS#7 padding"),
+    challenge!(ch2_10, 2, 10, "Implement CBC mode", true),
+    challenge!(ch2_11, 2, 11, "An ECB/CBC detection oracle"),
+    challenge!(ch2_12, 2, 12, "Byte-at-a-time ECB decryption (Simple)"),
+    challenge!(ch2_13, 2, 13, "ECB cut-and-paste"),
+    challenge!(ch2_14, 2, 14, "Byte-at-a-time ECB decryption (Harder)"),
+    challenge!(ch2_15, 2, 15, "PKCS#7 padding validation"),
+    challenge!(ch2_16, 2, 16, "CBC bitflipping attacks"),
+    challenge!(ch3_17, 3, 17, "The CBC padding oracle"),
+    challenge!(ch3_18, 3, 18, "Implement CTR, the stream cipher mode"),
+    challenge!(ch3_20, 3, 20, "Break fixed-nonce CTR statistically", true),
+];
+
+/// Which challenges to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    All,
+    Set(u8),
+    Challenge(u8),
+}
+
+impl Selection {
+    pub const fn matches(self, challenge: &Challenge) -> bool {
+        match self {
+            Self::All => true,
+            Self::Set(set) => challenge.set == set,
+            Self::Challenge(number) => challenge.number == number,
+        }
+    }
+
+    pub fn challenges(self) -> impl Iterator<Item = &'static Challenge> {
+        ALL.iter().filter(move |challenge| self.matches(challenge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenges_are_ordered() {
+        assert!(ALL
+            .windows(2)
+            .all(|pair| (pair[0].set, pair[0].number) < (pair[1].set, pair[1].number)));
+    }
+
+    #[test]
+    fn select_the_challenges() {
+        assert_eq!(Selection::All.challenges().count(), ALL.len());
+        let numbers: Vec<_> = Selection::Set(3)
+            .challenges()
+            .map(|challenge| challenge.number)
+            .collect();
+        assert_eq!(numbers, [17, 18, 20]);
+        assert_eq!(Selection::Challenge(13).challenges().count(), 1);
+        assert_eq!(Selection::Challenge(19).challenges().count(), 0);
+    }
+
+    #[test]
+    fn wrong_answers_are_rejected() {
+        for challenge in &ALL {
+            assert!(!(challenge.check)(""), "{}", challenge.number);
+        }
+    }
+}
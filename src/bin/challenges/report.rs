@@ -0,0 +1,220 @@
+use std::{
+    fmt::Write,
+    panic,
+    time::{Duration, Instant},
+};
+
+use pals::random;
+
+use super::registry::Challenge;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Passed,
+    /// The answer is not the expected one
+    Failed,
+    /// The solution returned an error or panicked
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub challenge: &'static Challenge,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.status == Status::Passed
+    }
+}
+
+/// Run the solution and check the answer.
+/// The randomness is restarted with the process seed for every challenge,
+/// so any of them can be replayed alone.
+pub fn run(challenge: &'static Challenge) -> Report {
+    random::reseed(random::seed());
+    let start = Instant::now();
+    let status = solve(challenge);
+    Report {
+        challenge,
+        status,
+        elapsed: start.elapsed(),
+    }
+}
+
+fn solve(challenge: &Challenge) -> Status {
+    match panic::catch_unwind(challenge.solve) {
+        Ok(Ok(answer)) if (challenge.check)(&answer) => Status::Passed,
+        Ok(Ok(_)) => Status::Failed,
+        Ok(Err(err)) => Status::Error(err.to_string()),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Status::Error(format!("Panicked: {}", message))
+        }
+    }
+}
+
+/// A line per challenge and the summary
+pub fn plain(reports: &[Report]) -> String {
+    let mut output = String::new();
+    for report in reports {
+        let challenge = report.challenge;
+        let status = match &report.status {
+            Status::Passed => "PASS".to_string(),
+            Status::Failed => "FAIL (wrong answer)".to_string(),
+            Status::Error(err) => format!("FAIL ({})", err),
+        };
+        let _ = writeln!(
+            output,
+            "set {} #{:<3} {:<40} {:>9.3}s  {}",
+            challenge.set,
+            challenge.number,
+            challenge.title,
+            report.elapsed.as_secs_f64(),
+            status
+        );
+    }
+
+    let passed = reports.iter().filter(|report| report.passed()).count();
+    let total: Duration = reports.iter().map(|report| report.elapsed).sum();
+    let _ = writeln!(
+        output,
+        "{} passed, {} failed in {:.3}s",
+        passed,
+        reports.len() - passed,
+        total.as_secs_f64()
+    );
+    if passed < reports.len() {
+        let _ = writeln!(output, "{}", random::replay_hint());
+    }
+    output
+}
+
+/// The array of the objects, one per challenge
+pub fn json(reports: &[Report]) -> String {
+    let items: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            let challenge = report.challenge;
+            let (status, error) = match &report.status {
+                Status::Passed => ("passed", None),
+                Status::Failed => ("failed", None),
+                Status::Error(err) => ("error", Some(err)),
+            };
+            format!(
+                r#"{{"set":{},"number":{},"title":{},"status":"{}","error":{},"seconds":{:.3},"seed":{}}}"#,
+                challenge.set,
+                challenge.number,
+                json_string(challenge.title),
+                status,
+                error.map_or_else(|| "null".to_string(), |err| json_string(err)),
+                report.elapsed.as_secs_f64(),
+                random::seed()
+            )
+        })
+        .collect();
+    format!("[{}]\n", items.join(","))
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", u32::from(ch));
+            }
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::registry::{Selection, ALL},
+        *,
+    };
+
+    #[test]
+    fn run_the_challenges_without_fixtures() {
+        for number in &[1, 2, 3, 5, 9, 13, 15, 16, 18] {
+            let challenge = Selection::Challenge(*number).challenges().next().unwrap();
+            assert!(!challenge.fixture);
+            let report = run(challenge);
+            assert_eq!(report.status, Status::Passed, "{}", number);
+        }
+    }
+
+    #[test]
+    fn errors_and_panics_are_reported() {
+        fn error() -> Result<String, Box<dyn std::error::Error>> {
+            Err("no input".into())
+        }
+
+        fn panic() -> Result<String, Box<dyn std::error::Error>> {
+            panic!("bad input")
+        }
+
+        let mut challenge = ALL[0];
+        challenge.solve = error;
+        assert_eq!(solve(&challenge), Status::Error("no input".to_string()));
+        challenge.solve = panic;
+        assert_eq!(
+            solve(&challenge),
+            Status::Error("Panicked: bad input".to_string())
+        );
+        challenge.solve = || Ok(String::new());
+        assert_eq!(solve(&challenge), Status::Failed);
+    }
+
+    #[test]
+    fn report_formats() {
+        let reports = vec![
+            Report {
+                challenge: &ALL[0],
+                status: Status::Passed,
+                elapsed: Duration::from_millis(5),
+            },
+            Report {
+                challenge: &ALL[1],
+                status: Status::Error("bad \"key\"\n".to_string()),
+                elapsed: Duration::from_millis(1500),
+            },
+        ];
+
+        let text = plain(&reports);
+        assert!(text.contains("Convert hex to base64"));
+        assert!(text.contains("1 passed, 1 failed in 1.505s\n"));
+        assert!(text.ends_with(&format!("PALS_SEED={}\n", random::seed())));
+
+        assert_eq!(
+            json(&reports),
+            format!(
+                "[{{\"set\":1,\"number\":1,\"title\":\"Convert hex to base64\",\
+                 \"status\":\"passed\",\"error\":null,\"seconds\":0.005,\"seed\":{0}}},\
+                 {{\"set\":1,\"number\":2,\"title\":\"Fixed XOR\",\
+                 \"status\":\"error\",\"error\":\"bad \\\"key\\\"\\n\",\"seconds\":1.500,\"seed\":{0}}}]\n",
+                random::seed()
+            )
+        );
+    }
+
+    #[test]
+    fn escape_the_control_symbols() {
+        assert_eq!(json_string("a\u{1}b\\"), r#""a\u0001b\\""#);
+    }
+}
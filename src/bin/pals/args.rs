@@ -0,0 +1,200 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+/// The command line split into the options and the positional arguments
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Args {
+    values: HashMap<String, String>,
+    flags: Vec<String>,
+    positional: Vec<String>,
+    verbosity: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+pub type Result<T> = std::result::Result<T, UsageError>;
+
+impl Args {
+    /// The options from the `with_values` list expect the value
+    /// either in the next argument (`--key value`) or after the `=` (`--key=value`).
+    /// All the other options are flags. Everything after the `--` is positional.
+    /// Every `v` in the short flags like `-v` or `-vv` increases the verbosity,
+    /// the other short options are unknown. The lone `-` stands for the stdin.
+    pub fn parse<I>(args: I, with_values: &[&str]) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                parsed.positional.extend(args.by_ref());
+                break;
+            }
+
+            if let Some(verbosity) = verbosity(&arg) {
+                parsed.verbosity += verbosity;
+                continue;
+            }
+
+            let Some(option) = arg.strip_prefix("--") else {
+                if arg.len() > 1 && arg.starts_with('-') {
+                    return Err(UsageError(format!("Unknown option {}", arg)));
+                }
+                parsed.positional.push(arg);
+                continue;
+            };
+
+            let (name, inline_value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (option, None),
+            };
+
+            if with_values.contains(&name) {
+                let value = inline_value
+                    .or_else(|| args.next())
+                    .ok_or_else(|| UsageError(format!("The option --{} needs a value", name)))?;
+                parsed.values.insert(name.to_string(), value);
+            } else if inline_value.is_some() {
+                return Err(UsageError(format!("The option --{} takes no value", name)));
+            } else {
+                parsed.flags.push(name.to_string());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// # Errors
+    /// The option is missing
+    pub fn required(&self, name: &str) -> Result<&str> {
+        self.value(name)
+            .ok_or_else(|| UsageError(format!("The option --{} is required", name)))
+    }
+
+    /// # Errors
+    /// The value cannot be parsed
+    pub fn parsed<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|err| UsageError(format!("Bad value for --{}: {}", name, err)))
+            })
+            .transpose()
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// The number of the `-v` flags
+    pub const fn verbosity(&self) -> usize {
+        self.verbosity
+    }
+
+    /// # Errors
+    /// Some option is not known to the command
+    pub fn ensure_known(&self, known: &[&str]) -> Result<()> {
+        let unknown = self
+            .flags
+            .iter()
+            .chain(self.values.keys())
+            .find(|name| !known.contains(&name.as_str()));
+        unknown.map_or(Ok(()), |name| {
+            Err(UsageError(format!("Unknown option --{}", name)))
+        })
+    }
+}
+
+/// The number of the `v` in the `-v`, `-vv`, etc
+fn verbosity(arg: &str) -> Option<usize> {
+    let flags = arg.strip_prefix('-')?;
+    if !flags.is_empty() && flags.bytes().all(|flag| flag == b'v') {
+        Some(flags.len())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options_and_positional() {
+        let parsed = Args::parse(
+            args("--key ICE --decode file.txt --in=base64"),
+            &["key", "in"],
+        )
+        .unwrap();
+        assert_eq!(parsed.value("key"), Some("ICE"));
+        assert_eq!(parsed.value("in"), Some("base64"));
+        assert!(parsed.flag("decode"));
+        assert!(!parsed.flag("upper"));
+        assert_eq!(parsed.positional(), &["file.txt".to_string()]);
+        assert!(parsed.ensure_known(&["key", "in", "decode"]).is_ok());
+        assert!(parsed.ensure_known(&["key", "in"]).is_err());
+    }
+
+    #[test]
+    fn everything_after_the_dashes_is_positional() {
+        let parsed = Args::parse(args("--upper -- --decode"), &[]).unwrap();
+        assert!(parsed.flag("upper"));
+        assert_eq!(parsed.positional(), &["--decode".to_string()]);
+    }
+
+    #[test]
+    fn count_the_verbosity() {
+        let parsed = Args::parse(args("-v --keys -vv -"), &[]).unwrap();
+        assert_eq!(parsed.verbosity(), 3);
+        assert!(parsed.flag("keys"));
+        assert_eq!(parsed.positional(), &["-".to_string()]);
+        assert_eq!(Args::parse(args("hex"), &[]).unwrap().verbosity(), 0);
+    }
+
+    #[test]
+    fn unknown_short_options() {
+        assert_eq!(
+            Args::parse(args("-x file.txt"), &[]),
+            Err(UsageError("Unknown option -x".to_string()))
+        );
+        assert!(Args::parse(args("-vx"), &[]).is_err());
+        assert!(Args::parse(args("-- -x"), &[]).is_ok());
+    }
+
+    #[test]
+    fn missing_and_unexpected_values() {
+        assert!(Args::parse(args("--key"), &["key"]).is_err());
+        assert!(Args::parse(args("--decode=yes"), &[]).is_err());
+
+        let parsed = Args::parse(args("--block-size 1x"), &["block-size"]).unwrap();
+        assert!(parsed.parsed::<u8>("block-size").is_err());
+        assert_eq!(parsed.parsed::<u8>("missing"), Ok(None));
+        assert!(parsed.required("missing").is_err());
+    }
+}
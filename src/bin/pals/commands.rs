@@ -0,0 +1,409 @@
+use std::{error::Error, fmt::Write};
+
+use pals::{
+    aes_cypher::{BlockMode, Builder, Padding},
+    attacks::{
+        ecb::repeated_blocks,
+        xor::{break_repeating_key, Config, KeySizeMethod},
+    },
+    encoding::{self, Encoding},
+    hex,
+    score::{ByteFrequency, LetterFrequency, NGrams, Scorer},
+    BytesCryptoExt, StreamCipher,
+};
+
+use super::args::{Args, UsageError};
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// The subcommands with the options they expect values for and the flags they accept
+pub const COMMANDS: [(&str, &[&str], &[&str]); 9] = [
+    ("hex", &[], &["decode", "upper"]),
+    ("b64", &["wrap"], &["decode", "url"]),
+    ("xor", &["key"], &[]),
+    (
+        "break-xor",
+        &[
+            "method",
+            "min-key-size",
+            "max-key-size",
+            "candidates",
+            "scorer",
+        ],
+        &["keys"],
+    ),
+    ("aes", &["mode", "key", "iv"], &["decrypt", "no-padding"]),
+    ("detect-ecb", &["block-size"], &[]),
+    ("pad", &["block-size"], &[]),
+    ("unpad", &["block-size"], &[]),
+    ("help", &[], &[]),
+];
+
+/// The options expecting the values for the command
+pub fn options_with_values(command: &str) -> Option<&'static [&'static str]> {
+    COMMANDS
+        .iter()
+        .find(|(name, ..)| *name == command)
+        .map(|(_, with_values, _)| *with_values)
+}
+
+pub const USAGE: &str = "\
+Usage: pals COMMAND [OPTIONS] [-v...] [FILE]
+
+Read the FILE (or the standard input if it is missing or `-`)
+and write the result to the standard output.
+Every `-v` shows more details of the process on the standard error.
+
+Commands:
+    hex [--decode] [--upper]
+    b64 [--decode] [--url] [--wrap WIDTH]
+    xor --key KEY
+    break-xor [--method hamming|ioc|friedman] [--min-key-size N] [--max-key-size N]
+              [--candidates N] [--scorer bytes|letters|quadgrams] [--keys]
+    aes --mode ecb|cbc --key KEY [--iv IV] [--decrypt] [--no-padding]
+    detect-ecb [--block-size N]
+    pad [--block-size N]
+    unpad [--block-size N]
+
+The KEY and IV are taken as is or decoded from hex with the `hex:` prefix.
+The `detect-ecb` expects a hex-encoded ciphertext on every line
+and prints the numbers of the lines with the repeated blocks.
+";
+
+fn usage(message: &str) -> Box<dyn Error> {
+    Box::new(UsageError(message.to_string()))
+}
+
+/// Run the command on the whole input
+///
+/// # Errors
+/// - the options are invalid for the command
+/// - the input cannot be processed
+pub fn run(command: &str, args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    let (_, with_values, flags) = COMMANDS
+        .iter()
+        .find(|(name, ..)| *name == command)
+        .ok_or_else(|| usage(&format!("Unknown command {:?}", command)))?;
+    let known: Vec<_> = with_values.iter().chain(flags.iter()).copied().collect();
+    args.ensure_known(&known)?;
+
+    match command {
+        "hex" => hex_command(args, input),
+        "b64" => b64_command(args, input),
+        "xor" => {
+            let key = parse_key(args.required("key")?)?;
+            if key.is_empty() {
+                return Err(usage("The key cannot be empty"));
+            }
+            Ok(input.xor_ref(key.iter()))
+        }
+        "break-xor" => break_xor_command(args, input),
+        "aes" => aes_command(args, input),
+        "detect-ecb" => detect_ecb_command(args, input),
+        "pad" | "unpad" => {
+            let block_size = args.parsed("block-size")?.unwrap_or(16);
+            // the PKCS#7 padding of the single byte blocks makes no sense
+            if block_size < 2 {
+                return Err(usage("The block size should be at least 2"));
+            }
+
+            let mut data = input.to_vec();
+            if command == "pad" {
+                data.pad_pkcs7(block_size);
+            } else {
+                data.unpad_pkcs7(block_size)?;
+            }
+            Ok(data)
+        }
+        "help" => Ok(USAGE.as_bytes().to_vec()),
+        _ => Err(usage(&format!("Unknown command {:?}", command))),
+    }
+}
+
+/// The text input with the trailing newline (if any) removed
+fn input_text(input: &[u8]) -> Result<&str> {
+    let text = std::str::from_utf8(input)?;
+    Ok(text.trim_end_matches(&['\r', '\n'][..]))
+}
+
+fn with_newline(mut text: String) -> Vec<u8> {
+    text.push('\n');
+    text.into_bytes()
+}
+
+fn hex_command(args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    if args.flag("decode") {
+        Ok(hex::decode_lenient(input_text(input)?)?)
+    } else if args.flag("upper") {
+        Ok(with_newline(hex::encode_upper(input)))
+    } else {
+        Ok(with_newline(hex::encode(input)))
+    }
+}
+
+fn b64_command(args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    let encoding = if args.flag("url") {
+        Encoding::Base64Url
+    } else {
+        Encoding::Base64
+    };
+
+    if args.flag("decode") {
+        return Ok(encoding.decode(input_text(input)?)?);
+    }
+
+    let encoded = encoding.encode(input);
+    let encoded = match args.parsed("wrap")? {
+        Some(0) => return Err(usage("The wrap width cannot be zero")),
+        Some(width) => encoding::wrap(&encoded, width),
+        None => encoded,
+    };
+    Ok(with_newline(encoded))
+}
+
+/// The key as is or decoded from hex with the `hex:` prefix
+fn parse_key(key: &str) -> Result<Vec<u8>> {
+    match key.strip_prefix("hex:") {
+        Some(hex_key) => Ok(hex::decode(hex_key)?),
+        None => Ok(key.as_bytes().to_vec()),
+    }
+}
+
+fn parse_method(method: &str) -> Result<KeySizeMethod> {
+    match method {
+        "hamming" => Ok(KeySizeMethod::Hamming),
+        "ioc" => Ok(KeySizeMethod::IndexOfCoincidence),
+        "friedman" => Ok(KeySizeMethod::Friedman),
+        _ => Err(usage(&format!("Unknown key size method {:?}", method))),
+    }
+}
+
+fn parse_scorer(scorer: &str) -> Result<Box<dyn Scorer>> {
+    match scorer {
+        "letters" => Ok(Box::new(LetterFrequency)),
+        "bytes" => Ok(Box::new(ByteFrequency::default())),
+        "quadgrams" => Ok(Box::new(NGrams::english_quadgrams())),
+        _ => Err(usage(&format!("Unknown scorer {:?}", scorer))),
+    }
+}
+
+fn break_xor_command(args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    let defaults = Config::default();
+    let method = args
+        .value("method")
+        .map_or(Ok(KeySizeMethod::Hamming), parse_method)?;
+    let min_key_size = args.parsed("min-key-size")?.unwrap_or(2);
+    let max_key_size = args.parsed("max-key-size")?.unwrap_or(40);
+    let mut config = defaults
+        .method(method)
+        .key_sizes(min_key_size..=max_key_size);
+    if let Some(candidates) = args.parsed("candidates")? {
+        config = config.candidates(candidates);
+    }
+    let scorer = args.value("scorer").map_or_else(
+        || Ok(Box::new(ByteFrequency::default()) as Box<dyn Scorer>),
+        parse_scorer,
+    )?;
+
+    let candidates = break_repeating_key(input, &config, scorer.as_ref());
+    if args.flag("keys") {
+        let mut lines = String::new();
+        for candidate in &candidates {
+            writeln!(
+                lines,
+                "{}\t{}\t{:?}",
+                candidate.text_score,
+                hex::encode(&candidate.key),
+                String::from_utf8_lossy(&candidate.key)
+            )?;
+        }
+        return Ok(lines.into_bytes());
+    }
+
+    let best = candidates
+        .first()
+        .ok_or_else(|| usage("No key found, try the other key sizes"))?;
+    Ok(input.xor_ref(best.key.iter()))
+}
+
+fn aes_command(args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    let key = parse_key(args.required("key")?)?;
+    let mut builder = Builder::new(&key)?;
+    if let Some(iv) = args.value("iv") {
+        builder = builder.iv(&parse_key(iv)?)?;
+    }
+    if args.flag("no-padding") {
+        builder = builder.padding(Padding::NoPadding);
+    }
+
+    let cipher: Box<dyn BlockMode> = match args.required("mode")? {
+        "ecb" => Box::new(builder.ecb()),
+        "cbc" => Box::new(builder.cbc()),
+        mode => return Err(usage(&format!("Unknown mode {:?}", mode))),
+    };
+
+    if args.flag("decrypt") {
+        Ok(cipher.decrypt(input)?)
+    } else {
+        Ok(cipher.encrypt(input)?)
+    }
+}
+
+fn detect_ecb_command(args: &Args, input: &[u8]) -> Result<Vec<u8>> {
+    let block_size = args.parsed("block-size")?.unwrap_or(16);
+    let mut output = String::new();
+    for (i, line) in std::str::from_utf8(input)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let data = hex::decode_lenient(line)?;
+        let repeated = repeated_blocks(&data, block_size);
+        if repeated > 0 {
+            writeln!(output, "{}\t{}", i, repeated)?;
+        }
+    }
+    Ok(output.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_line(line: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let mut words = line.split_whitespace().map(String::from);
+        let command = words.next().unwrap();
+        let with_values = options_with_values(&command).unwrap_or_default();
+        let args = Args::parse(words, with_values)?;
+        run(&command, &args, input)
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let encoded = run_line("hex --upper", b"\x49\x27\x6d").unwrap();
+        assert_eq!(encoded, b"49276D\n");
+        let decoded = run_line("hex --decode", &encoded).unwrap();
+        assert_eq!(decoded, b"\x49\x27\x6d");
+    }
+
+    #[test]
+    fn b64_of_the_hex() {
+        // challenge 1
+        let data = run_line(
+            "hex --decode",
+            b"49276d206b696c6c696e6720796f757220627261696e206c696b65206120706f69736f6e6f7573206d757368726f6f6d\n",
+        )
+        .unwrap();
+        let encoded = run_line("b64", &data).unwrap();
+        assert_eq!(
+            encoded,
+            b"SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t\n"
+        );
+        assert_eq!(run_line("b64 --wrap 32", &data).unwrap().len(), 64 + 2);
+        assert_eq!(run_line("b64 --decode", &encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn xor_with_the_key() {
+        // challenge 5
+        let plain = b"Burning 'em, if you ain't quick and nimble\n\
+            I go crazy when I hear a cymbal";
+        let encrypted = run_line("xor --key ICE", plain).unwrap();
+        assert_eq!(
+            hex::encode(&encrypted),
+            "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63343c2a26226324272765272\
+             a282b2f20430a652e2c652a3124333a653e2b2027630c692b20283165286326302e27282f"
+        );
+        assert_eq!(run_line("xor --key hex:494345", &encrypted).unwrap(), plain);
+        assert!(run_line("xor", plain).is_err());
+        assert!(run_line("xor --key hex:", plain).is_err());
+    }
+
+    #[test]
+    fn xor_the_executable() {
+        let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let encrypted = run_line("xor --key ICE", &exe).unwrap();
+        assert_ne!(encrypted, exe);
+        // decrypting is the same for XOR
+        assert_eq!(run_line("xor --key ICE", &encrypted).unwrap(), exe);
+    }
+
+    #[test]
+    fn break_the_xor() {
+        let plain = "It was the best of times, it was the worst of times, \
+            it was the age of wisdom, it was the age of foolishness, \
+            it was the epoch of belief, it was the epoch of incredulity, \
+            it was the season of Light, it was the season of Darkness, \
+            it was the spring of hope, it was the winter of despair.";
+        let encrypted = plain.xor(b"Vanilla".iter().copied());
+
+        let decrypted = run_line("break-xor --max-key-size 10", &encrypted).unwrap();
+        assert_eq!(decrypted, plain.as_bytes());
+
+        // the multiples of the key size decrypt the data as well
+        let keys = run_line(
+            "break-xor --keys --method ioc --scorer bytes --max-key-size 20",
+            &encrypted,
+        )
+        .unwrap();
+        let keys = String::from_utf8(keys).unwrap();
+        assert!(keys.lines().any(|line| line.ends_with("\"Vanilla\"")));
+        assert!(keys
+            .lines()
+            .all(|line| line.contains("\"VanillaVanilla") || line.ends_with("\"Vanilla\"")));
+
+        assert!(run_line("break-xor --method guess", &encrypted).is_err());
+    }
+
+    #[test]
+    fn aes_roundtrip() {
+        let plain = b"Play that funky music";
+        let encrypted = run_line(
+            "aes --mode cbc --key YELLOW_SUBMARINE --iv hex:000102030405060708090a0b0c0d0e0f",
+            plain,
+        )
+        .unwrap();
+        assert_eq!(encrypted.len(), 32);
+        let decrypted = run_line(
+            "aes --mode cbc --key YELLOW_SUBMARINE --iv hex:000102030405060708090a0b0c0d0e0f --decrypt",
+            &encrypted,
+        )
+        .unwrap();
+        assert_eq!(decrypted, plain);
+
+        assert!(run_line("aes --mode ecb --key YELLOW_SUBMARINE --no-padding", plain).is_err());
+        assert!(run_line("aes --mode ctr --key YELLOW_SUBMARINE", plain).is_err());
+        assert!(run_line("aes --mode ecb --key short", plain).is_err());
+    }
+
+    #[test]
+    fn detect_the_ecb_lines() {
+        let ecb = run_line("aes --mode ecb --key YELLOW_SUBMARINE", &[b'A'; 48]).unwrap();
+        let cbc = run_line("aes --mode cbc --key YELLOW_SUBMARINE", &[b'A'; 48]).unwrap();
+        let input = format!("{}\n\n{}\n", hex::encode(&cbc), hex::encode(&ecb));
+        let detected = run_line("detect-ecb", input.as_bytes()).unwrap();
+        assert_eq!(detected, b"2\t2\n");
+    }
+
+    #[test]
+    fn pad_and_unpad() {
+        // challenge 9
+        let padded = run_line("pad --block-size 20", b"YELLOW SUBMARINE").unwrap();
+        assert_eq!(padded, b"YELLOW SUBMARINE\x04\x04\x04\x04");
+        assert_eq!(
+            run_line("unpad --block-size 20", &padded).unwrap(),
+            b"YELLOW SUBMARINE"
+        );
+        assert!(run_line("unpad", b"ICE ICE BABY\x01\x02\x03\x04").is_err());
+        assert!(run_line("pad --block-size 0", b"").is_err());
+        assert!(run_line("pad --block-size 1", b"").is_err());
+        assert!(run_line("unpad --block-size 1", b"\x01").is_err());
+    }
+
+    #[test]
+    fn unknown_commands_and_options() {
+        assert!(run_line("rot13", b"").is_err());
+        assert!(run_line("hex --lower", b"").is_err());
+        assert!(run_line("help", b"").is_ok());
+    }
+}
@@ -0,0 +1,71 @@
+//! The command-line swiss knife for the everyday cryptanalysis.
+//!
+//! Every command reads the whole input and writes the whole output,
+//! so they can be chained in the shell pipelines:
+//!
+//! ```sh
+//! pals b64 --decode data/6.txt | pals break-xor
+//! ```
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    process,
+};
+
+mod args;
+mod commands;
+
+use pals::logging;
+
+use self::{
+    args::{Args, UsageError},
+    commands::USAGE,
+};
+
+/// The exit code for the invalid command line
+const USAGE_EXIT_CODE: i32 = 2;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("pals: {}", err);
+        if err.is::<UsageError>() {
+            eprint!("\n{}", USAGE);
+            process::exit(USAGE_EXIT_CODE);
+        }
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut words = env::args().skip(1);
+    let command = words
+        .next()
+        .ok_or_else(|| UsageError("The command is required".to_string()))?;
+    let with_values = commands::options_with_values(&command)
+        .ok_or_else(|| UsageError(format!("Unknown command {:?}", command)))?;
+    let args = Args::parse(words, with_values)?;
+    logging::init(logging::level(args.verbosity()))?;
+
+    let input = match args.positional() {
+        _ if command == "help" => Vec::new(),
+        [] => read_stdin()?,
+        [path] if path == "-" => read_stdin()?,
+        [path] => fs::read(path)?,
+        _ => return Err(UsageError("Only a single file is expected".to_string()).into()),
+    };
+
+    let output = commands::run(&command, &args, &input)?;
+    let mut stdout = io::stdout();
+    stdout.write_all(&output)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn read_stdin() -> io::Result<Vec<u8>> {
+    let mut input = Vec::new();
+    io::stdin().read_to_end(&mut input)?;
+    Ok(input)
+}
@@ -0,0 +1,113 @@
+//! Affine cipher: the letter `x` becomes `a * x + b`.
+//! The `a` should be coprime with the alphabet size to decrypt.
+
+use crate::{score::Scorer, Error, Result};
+
+use super::{map_letters, Cipher, ALPHABET_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine {
+    a: u8,
+    b: u8,
+    a_inverse: u8,
+}
+
+/// The multiplicative inverse of the `a` modulo the alphabet size
+fn inverse(a: u8) -> Option<u8> {
+    (1..ALPHABET_SIZE).find(|x| (u16::from(a) * u16::from(*x)) % u16::from(ALPHABET_SIZE) == 1)
+}
+
+impl Affine {
+    /// # Errors
+    /// The `a` is not coprime with the alphabet size
+    pub fn new(a: u8, b: u8) -> Result<Self> {
+        let a = a % ALPHABET_SIZE;
+        let a_inverse = inverse(a).ok_or(Error::BadKey(
+            "the affine multiplier should be coprime with 26",
+        ))?;
+        Ok(Self {
+            a,
+            b: b % ALPHABET_SIZE,
+            a_inverse,
+        })
+    }
+
+    pub const fn a(self) -> u8 {
+        self.a
+    }
+
+    pub const fn b(self) -> u8 {
+        self.b
+    }
+
+    /// All the 312 valid keys
+    pub fn all() -> impl Iterator<Item = Self> {
+        (1..ALPHABET_SIZE)
+            .flat_map(|a| (0..ALPHABET_SIZE).filter_map(move |b| Self::new(a, b).ok()))
+    }
+}
+
+impl Cipher for Affine {
+    fn encrypt(&self, plain: &str) -> String {
+        #[allow(clippy::cast_possible_truncation)]
+        map_letters(plain, |x| {
+            ((u16::from(self.a) * u16::from(x) + u16::from(self.b)) % u16::from(ALPHABET_SIZE))
+                as u8
+        })
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        #[allow(clippy::cast_possible_truncation)]
+        map_letters(cipher_text, |y| {
+            let shifted = u16::from(y + ALPHABET_SIZE - self.b);
+            ((u16::from(self.a_inverse) * shifted) % u16::from(ALPHABET_SIZE)) as u8
+        })
+    }
+}
+
+/// Try every valid key, the best scored decryptions first
+pub fn crack<S>(cipher_text: &str, scorer: &S) -> Vec<(Affine, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let mut candidates: Vec<_> = Affine::all()
+        .map(|affine| {
+            let score = scorer.score(&affine.decrypt(cipher_text));
+            (affine, score)
+        })
+        .collect();
+
+    candidates.sort_by_key(|(affine, score)| (*score, affine.a, affine.b));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::NGrams;
+
+    use super::*;
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let affine = Affine::new(5, 8).unwrap();
+        assert_eq!(affine.encrypt("Affine cipher"), "Ihhwvc swfrcp");
+        assert_eq!(affine.decrypt("Ihhwvc swfrcp"), "Affine cipher");
+    }
+
+    #[test]
+    fn multiplier_should_be_invertible() {
+        assert!(Affine::new(13, 1).is_err());
+        assert!(Affine::new(4, 1).is_err());
+        assert_eq!(Affine::all().count(), 12 * 26);
+    }
+
+    #[test]
+    fn crack_the_key() {
+        let plain = "It is a truth universally acknowledged";
+        let affine = Affine::new(7, 3).unwrap();
+        let cipher_text = affine.encrypt(plain);
+
+        let candidates = crack(&cipher_text, NGrams::english_quadgrams());
+        assert_eq!(candidates[0].0, affine);
+    }
+}
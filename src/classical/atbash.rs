@@ -0,0 +1,58 @@
+//! Atbash cipher: the alphabet is reversed, so the `a` becomes `z` and vice versa.
+
+use crate::score::Scorer;
+
+use super::{map_letters, Cipher, ALPHABET_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Atbash;
+
+impl Cipher for Atbash {
+    fn encrypt(&self, plain: &str) -> String {
+        map_letters(plain, |i| ALPHABET_SIZE - 1 - i)
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.encrypt(cipher_text)
+    }
+}
+
+/// There is no key to guess, so just tell whether the text is encrypted at all.
+///
+/// Returns the decryption if it scores better than the text itself.
+pub fn crack<S>(cipher_text: &str, scorer: &S) -> Option<(String, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let plain = Atbash.decrypt(cipher_text);
+    let score = scorer.score(&plain);
+    if score < scorer.score(cipher_text) {
+        Some((plain, score))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::NGrams;
+
+    use super::*;
+
+    #[test]
+    fn reversed_alphabet() {
+        assert_eq!(Atbash.encrypt("Abc xyZ!"), "Zyx cbA!");
+        assert_eq!(Atbash.decrypt(&Atbash.encrypt("Wizard")), "Wizard");
+    }
+
+    #[test]
+    fn crack_only_the_encrypted() {
+        let plain = "Meet me at the old lighthouse after dark";
+        let cipher_text = Atbash.encrypt(plain);
+
+        let quadgrams = NGrams::english_quadgrams();
+        let (cracked, _score) = crack(&cipher_text, quadgrams).unwrap();
+        assert_eq!(cracked, plain);
+        assert!(crack(plain, quadgrams).is_none());
+    }
+}
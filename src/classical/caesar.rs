@@ -0,0 +1,75 @@
+//! Caesar cipher: every letter is shifted by the same number of positions.
+
+use crate::score::Scorer;
+
+use super::{map_letters, Cipher, ALPHABET_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caesar {
+    shift: u8,
+}
+
+impl Caesar {
+    pub const fn new(shift: u8) -> Self {
+        Self {
+            shift: shift % ALPHABET_SIZE,
+        }
+    }
+
+    pub const fn shift(self) -> u8 {
+        self.shift
+    }
+}
+
+impl Cipher for Caesar {
+    fn encrypt(&self, plain: &str) -> String {
+        map_letters(plain, |i| i + self.shift)
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        map_letters(cipher_text, |i| i + ALPHABET_SIZE - self.shift)
+    }
+}
+
+/// Try every shift, the best scored decryptions first
+pub fn crack<S>(cipher_text: &str, scorer: &S) -> Vec<(Caesar, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let mut candidates: Vec<_> = (0..ALPHABET_SIZE)
+        .map(|shift| {
+            let caesar = Caesar::new(shift);
+            let score = scorer.score(&caesar.decrypt(cipher_text));
+            (caesar, score)
+        })
+        .collect();
+
+    candidates.sort_by_key(|(caesar, score)| (*score, caesar.shift));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::NGrams;
+
+    use super::*;
+
+    #[test]
+    fn rot13() {
+        let rot13 = Caesar::new(13);
+        assert_eq!(rot13.encrypt("Hello, World!"), "Uryyb, Jbeyq!");
+        assert_eq!(rot13.decrypt("Uryyb, Jbeyq!"), "Hello, World!");
+        assert_eq!(Caesar::new(29), Caesar::new(3));
+    }
+
+    #[test]
+    fn crack_the_shift() {
+        let plain = "The quick brown fox jumps over the lazy dog";
+        let cipher_text = Caesar::new(7).encrypt(plain);
+
+        let candidates = crack(&cipher_text, NGrams::english_quadgrams());
+        assert_eq!(candidates.len(), 26);
+        assert_eq!(candidates[0].0, Caesar::new(7));
+        assert_eq!(candidates[0].0.decrypt(&cipher_text), plain);
+    }
+}
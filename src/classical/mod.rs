@@ -0,0 +1,63 @@
+//! Classical (pen and paper) ciphers over the Latin alphabet.
+//!
+//! The substitution ciphers encrypt only the ASCII letters keeping their case,
+//! all the other symbols (spaces, punctuation) are left intact.
+//! The [`transposition`] ciphers move every symbol.
+//! Every cipher comes with the automatic breaker ranking the keys
+//! with the [`Scorer`](crate::score::Scorer), the quadgrams work best.
+
+pub mod affine;
+pub mod atbash;
+pub mod caesar;
+pub mod substitution;
+pub mod transposition;
+pub mod vigenere;
+
+/// The number of letters in the Latin alphabet
+pub const ALPHABET_SIZE: u8 = 26;
+
+pub trait Cipher {
+    fn encrypt(&self, plain: &str) -> String;
+
+    fn decrypt(&self, cipher_text: &str) -> String;
+}
+
+/// Replace every letter with the one at the given index (0 for `'a'`)
+/// keeping the case and the other symbols as is
+fn map_letters(text: &str, mut f: impl FnMut(u8) -> u8) -> String {
+    text.chars()
+        .map(|ch| {
+            if !ch.is_ascii_alphabetic() {
+                return ch;
+            }
+
+            let base = if ch.is_ascii_uppercase() { b'A' } else { b'a' };
+            let index = ch as u8 - base;
+            char::from(base + f(index) % ALPHABET_SIZE)
+        })
+        .collect()
+}
+
+/// The indices of all the letters of the text (0 for `'a'`)
+fn letter_indices(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|byte| byte.to_ascii_lowercase() - b'a')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_letters_are_mapped() {
+        assert_eq!(map_letters("Hello, World!", |i| i + 1), "Ifmmp, Xpsme!");
+        assert_eq!(map_letters("xyz XYZ", |i| i + 3), "abc ABC");
+    }
+
+    #[test]
+    fn indices_of_letters() {
+        assert_eq!(letter_indices("Ab, z!"), vec![0, 1, 25]);
+    }
+}
@@ -0,0 +1,247 @@
+//! Monoalphabetic substitution: every letter is replaced by the letter
+//! at the same position of the permuted alphabet.
+//!
+//! There are 26! keys, so the breaker climbs the hill:
+//! starting with the key matching the letters frequencies,
+//! it swaps the pairs of letters while the score of the decryption improves,
+//! and restarts from the shuffled keys to escape the local optimum.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{freq, random, score::Scorer, Error, Result};
+
+use super::{letter_indices, map_letters, Cipher, ALPHABET_SIZE};
+
+const ALPHABET_LEN: usize = ALPHABET_SIZE as usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    /// The cipher letter for every plain letter
+    key: [u8; ALPHABET_LEN],
+    /// The plain letter for every cipher letter
+    inverse: [u8; ALPHABET_LEN],
+}
+
+impl Substitution {
+    /// The key is the permuted alphabet: the letter for `a`, the letter for `b`, etc.
+    ///
+    /// # Errors
+    /// The key is not a permutation of the 26 letters
+    pub fn new(key: &str) -> Result<Self> {
+        let letters = letter_indices(key);
+        if key.len() != ALPHABET_LEN || letters.len() != ALPHABET_LEN {
+            return Err(Error::BadKey("the substitution key should have 26 letters"));
+        }
+
+        let mut permutation = [0; ALPHABET_LEN];
+        permutation.copy_from_slice(&letters);
+        Self::from_permutation(permutation)
+            .ok_or(Error::BadKey("every letter should appear in the key once"))
+    }
+
+    fn from_permutation(key: [u8; ALPHABET_LEN]) -> Option<Self> {
+        let mut inverse = [ALPHABET_SIZE; ALPHABET_LEN];
+        for (plain, &cipher) in (0..).zip(&key) {
+            let slot = &mut inverse[usize::from(cipher)];
+            if *slot != ALPHABET_SIZE {
+                return None;
+            }
+            *slot = plain;
+        }
+        Some(Self { key, inverse })
+    }
+
+    /// The lowercase permuted alphabet
+    pub fn key(&self) -> String {
+        self.key
+            .iter()
+            .map(|&letter| char::from(b'a' + letter))
+            .collect()
+    }
+}
+
+impl Cipher for Substitution {
+    fn encrypt(&self, plain: &str) -> String {
+        map_letters(plain, |i| self.key[usize::from(i)])
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        map_letters(cipher_text, |i| self.inverse[usize::from(i)])
+    }
+}
+
+/// The hill climbing settings
+#[derive(Debug, Clone)]
+pub struct HillClimbing {
+    restarts: usize,
+    patience: usize,
+}
+
+impl Default for HillClimbing {
+    fn default() -> Self {
+        Self {
+            restarts: 5,
+            patience: 2000,
+        }
+    }
+}
+
+impl HillClimbing {
+    /// How many times to start over from the random key
+    #[must_use]
+    pub const fn restarts(mut self, restarts: usize) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    /// How many random swaps in a row can fail to improve the score
+    /// before giving up the current climb
+    #[must_use]
+    pub const fn patience(mut self, patience: usize) -> Self {
+        self.patience = patience;
+        self
+    }
+}
+
+/// Find the key with the best scored decryption (the lower the better).
+/// Use the quadgrams as the scorer, the letters frequencies are too rough here.
+pub fn crack<S>(cipher_text: &str, scorer: &S, settings: &HillClimbing) -> (Substitution, u64)
+where
+    S: Scorer + ?Sized,
+{
+    crack_with(cipher_text, scorer, settings, &mut random::rng())
+}
+
+/// Same as the [`crack`] with the given source of the random restarts and swaps
+pub fn crack_with<S, R>(
+    cipher_text: &str,
+    scorer: &S,
+    settings: &HillClimbing,
+    rng: &mut R,
+) -> (Substitution, u64)
+where
+    S: Scorer + ?Sized,
+    R: Rng + ?Sized,
+{
+    let score = |key: &Substitution| scorer.score(&key.decrypt(cipher_text));
+
+    let mut best = by_frequencies(cipher_text);
+    let mut best_score = score(&best);
+
+    for restart in 0..=settings.restarts {
+        let mut current = if restart == 0 {
+            best.clone()
+        } else {
+            let mut key = best.key;
+            key.shuffle(rng);
+            Substitution::from_permutation(key).unwrap_or_else(|| best.clone())
+        };
+        let mut current_score = score(&current);
+
+        let mut failures = 0;
+        while failures < settings.patience {
+            let mut key = current.key;
+            let i = rng.gen_range(0, ALPHABET_LEN);
+            let j = rng.gen_range(0, ALPHABET_LEN);
+            if i == j {
+                continue;
+            }
+            key.swap(i, j);
+
+            let Some(candidate) = Substitution::from_permutation(key) else {
+                continue;
+            };
+            let candidate_score = score(&candidate);
+            if candidate_score < current_score {
+                current = candidate;
+                current_score = candidate_score;
+                failures = 0;
+            } else {
+                failures += 1;
+            }
+        }
+
+        if current_score < best_score {
+            best = current;
+            best_score = current_score;
+        }
+    }
+
+    (best, best_score)
+}
+
+/// The key mapping the letters of the text sorted by their frequencies
+/// to the English letters sorted the same way
+fn by_frequencies(cipher_text: &str) -> Substitution {
+    let mut counts = [0_usize; ALPHABET_LEN];
+    for letter in letter_indices(cipher_text) {
+        counts[usize::from(letter)] += 1;
+    }
+
+    let mut cipher_letters: Vec<u8> = (0..ALPHABET_SIZE).collect();
+    cipher_letters.sort_by_key(|&letter| std::cmp::Reverse(counts[usize::from(letter)]));
+
+    let mut english: Vec<_> = freq::eng_map().into_iter().collect();
+    english.sort_by(|(ch1, freq1), (ch2, freq2)| freq2.total_cmp(freq1).then(ch1.cmp(ch2)));
+
+    let mut key = [0; ALPHABET_LEN];
+    for ((plain, _freq), &cipher) in english.iter().zip(&cipher_letters) {
+        key[usize::from(*plain as u8 - b'A')] = cipher;
+    }
+    Substitution::from_permutation(key).expect("Every letter is mapped once")
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::score::NGrams;
+
+    use super::*;
+
+    const KEY: &str = "qwertyuiopasdfghjklzxcvbnm";
+
+    const PLAIN: &str = "The keeper of the lighthouse had not seen a ship for three weeks, \
+        so when the lamp went dark on the night of the storm, nobody in the village \
+        noticed it until the morning. The fishermen gathered on the pier and argued \
+        about who should row out to the rock, because the waves were still high \
+        and the wind kept turning. At last the youngest of them took the boat alone, \
+        and found the old man asleep by the cold stove with a letter in his hands.";
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let substitution = Substitution::new(KEY).unwrap();
+        assert_eq!(substitution.key(), KEY);
+        assert_eq!(substitution.encrypt("Hello, World!"), "Itssg, Vgksr!");
+        assert_eq!(substitution.decrypt("Itssg, Vgksr!"), "Hello, World!");
+    }
+
+    #[test]
+    fn bad_key() {
+        assert!(Substitution::new("abc").is_err());
+        assert!(Substitution::new("qwertyuiopasdfghjklzxcvbnq").is_err());
+        assert!(Substitution::new("qwertyuiopasdfghjklzxcvb1m").is_err());
+    }
+
+    #[test]
+    fn climb_the_hill() {
+        let substitution = Substitution::new(KEY).unwrap();
+        let cipher_text = substitution.encrypt(PLAIN);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let (cracked, _score) = crack_with(
+            &cipher_text,
+            NGrams::english_quadgrams(),
+            &HillClimbing::default(),
+            &mut rng,
+        );
+        let decrypted = cracked.decrypt(&cipher_text);
+        let right = decrypted
+            .chars()
+            .zip(PLAIN.chars())
+            .filter(|(a, b)| a == b)
+            .count();
+        // the rare letters may stay swapped
+        assert!(right * 100 >= PLAIN.len() * 95, "{}", decrypted);
+    }
+}
@@ -0,0 +1,378 @@
+//! Transposition ciphers: the symbols stay the same but change their places.
+//!
+//! Unlike the rest of the classical ciphers, every symbol is moved,
+//! including the spaces and punctuation.
+//! The letters frequencies do not change either, so only the n-grams
+//! can tell the right order, see the [`NGrams`](crate::score::NGrams).
+
+use itertools::Itertools;
+
+use crate::{score::Scorer, Error, Result};
+
+use super::Cipher;
+
+/// The symbol at the position `i` of the ciphertext
+/// is the symbol at the position `positions[i]` of the plaintext
+fn gather(text: &str, positions: impl Fn(usize) -> Vec<usize>) -> String {
+    let symbols: Vec<char> = text.chars().collect();
+    positions(symbols.len())
+        .into_iter()
+        .map(|position| symbols[position])
+        .collect()
+}
+
+/// The reverse of the [`gather`]
+fn scatter(text: &str, positions: impl Fn(usize) -> Vec<usize>) -> String {
+    let symbols: Vec<char> = text.chars().collect();
+    let mut plain = vec![' '; symbols.len()];
+    for (&position, &symbol) in positions(symbols.len()).iter().zip(&symbols) {
+        plain[position] = symbol;
+    }
+    plain.into_iter().collect()
+}
+
+fn rank_candidates<C, S>(cipher_text: &str, candidates: Vec<C>, scorer: &S) -> Vec<(C, u64)>
+where
+    C: Cipher,
+    S: Scorer + ?Sized,
+{
+    let mut candidates: Vec<_> = candidates
+        .into_iter()
+        .map(|cipher| {
+            let score = scorer.score(&cipher.decrypt(cipher_text));
+            (cipher, score)
+        })
+        .collect();
+    // stable sort keeps the simpler keys first among the equal ones
+    candidates.sort_by_key(|(_cipher, score)| *score);
+    candidates
+}
+
+/// The text is written in rows of the key size
+/// and read by columns in the order of the key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columnar {
+    /// The columns in the order of reading
+    order: Vec<usize>,
+}
+
+impl Columnar {
+    /// # Errors
+    /// The order is not a permutation of the columns indices
+    pub fn new(order: Vec<usize>) -> Result<Self> {
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if sorted.is_empty() || !sorted.iter().copied().eq(0..order.len()) {
+            return Err(Error::BadKey(
+                "the columns order should be a permutation of the columns",
+            ));
+        }
+        Ok(Self { order })
+    }
+
+    /// The columns are read in the alphabetical order of the keyword letters
+    ///
+    /// # Errors
+    /// The keyword is empty
+    pub fn from_keyword(keyword: &str) -> Result<Self> {
+        let letters: Vec<_> = keyword.chars().map(|ch| ch.to_ascii_lowercase()).collect();
+        let mut order: Vec<_> = (0..letters.len()).collect();
+        order.sort_by_key(|&column| letters[column]);
+        Self::new(order)
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    fn positions(&self, len: usize) -> Vec<usize> {
+        let columns = self.order.len();
+        self.order
+            .iter()
+            .flat_map(|&column| (column..len).step_by(columns))
+            .collect()
+    }
+}
+
+impl Cipher for Columnar {
+    fn encrypt(&self, plain: &str) -> String {
+        gather(plain, |len| self.positions(len))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        scatter(cipher_text, |len| self.positions(len))
+    }
+}
+
+/// Try every order of the columns for every number of the columns
+/// up to the `max_columns`, the best scored decryptions first.
+///
+/// The number of the orders grows as the factorial,
+/// so keep the `max_columns` under 9.
+pub fn crack_columnar<S>(cipher_text: &str, max_columns: usize, scorer: &S) -> Vec<(Columnar, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let len = cipher_text.chars().count();
+    let candidates = (2..=max_columns.min(len))
+        .flat_map(|columns| (0..columns).permutations(columns))
+        .map(|order| Columnar { order })
+        .collect();
+    rank_candidates(cipher_text, candidates, scorer)
+}
+
+/// The text is written in the zigzag over the rails
+/// and read rail by rail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RailFence {
+    rails: usize,
+}
+
+impl RailFence {
+    /// # Errors
+    /// Less than two rails
+    pub const fn new(rails: usize) -> Result<Self> {
+        if rails < 2 {
+            return Err(Error::BadKey(
+                "the rail fence should have two rails or more",
+            ));
+        }
+        Ok(Self { rails })
+    }
+
+    pub const fn rails(self) -> usize {
+        self.rails
+    }
+
+    fn positions(self, len: usize) -> Vec<usize> {
+        let cycle = 2 * (self.rails - 1);
+        let rail = |position: usize| {
+            let phase = position % cycle;
+            phase.min(cycle - phase)
+        };
+        (0..self.rails)
+            .flat_map(|current| (0..len).filter(move |&position| rail(position) == current))
+            .collect()
+    }
+}
+
+impl Cipher for RailFence {
+    fn encrypt(&self, plain: &str) -> String {
+        gather(plain, |len| self.positions(len))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        scatter(cipher_text, |len| self.positions(len))
+    }
+}
+
+/// Try every number of the rails up to the `max_rails`, the best scored decryptions first
+pub fn crack_rail_fence<S>(cipher_text: &str, max_rails: usize, scorer: &S) -> Vec<(RailFence, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let len = cipher_text.chars().count();
+    let candidates = (2..=max_rails.min(len))
+        .map(|rails| RailFence { rails })
+        .collect();
+    rank_candidates(cipher_text, candidates, scorer)
+}
+
+/// The way to read the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Path {
+    /// Clockwise from the top left corner to the center
+    Spiral,
+    /// Down the first column, up the second, down the third and so on
+    Snake,
+}
+
+impl Path {
+    pub const ALL: [Self; 2] = [Self::Spiral, Self::Snake];
+}
+
+/// The text is written in rows of the given size
+/// and read along the path. The last row may be incomplete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+    columns: usize,
+    path: Path,
+}
+
+impl Route {
+    /// # Errors
+    /// No columns
+    pub const fn new(columns: usize, path: Path) -> Result<Self> {
+        if columns == 0 {
+            return Err(Error::BadKey("the route grid should have columns"));
+        }
+        Ok(Self { columns, path })
+    }
+
+    pub const fn columns(self) -> usize {
+        self.columns
+    }
+
+    pub const fn path(self) -> Path {
+        self.path
+    }
+
+    fn positions(self, len: usize) -> Vec<usize> {
+        let columns = self.columns;
+        let rows = (len + columns - 1) / columns;
+        let cells = match self.path {
+            Path::Spiral => spiral(rows, columns),
+            Path::Snake => (0..columns)
+                .flat_map(|column| {
+                    let down = (0..rows).map(move |row| (row, column));
+                    if column % 2 == 0 {
+                        down.collect::<Vec<_>>()
+                    } else {
+                        down.rev().collect()
+                    }
+                })
+                .collect(),
+        };
+
+        cells
+            .into_iter()
+            .map(|(row, column)| row * columns + column)
+            .filter(|&position| position < len)
+            .collect()
+    }
+}
+
+/// The cells of the grid clockwise from the top left corner to the center
+fn spiral(rows: usize, columns: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::with_capacity(rows * columns);
+    let (mut top, mut left) = (0, 0);
+    let (mut bottom, mut right) = (rows, columns);
+    while top < bottom && left < right {
+        cells.extend((left..right).map(|column| (top, column)));
+        cells.extend((top + 1..bottom).map(|row| (row, right - 1)));
+        if top + 1 < bottom {
+            cells.extend((left..right - 1).rev().map(|column| (bottom - 1, column)));
+        }
+        if left + 1 < right {
+            cells.extend((top + 1..bottom - 1).rev().map(|row| (row, left)));
+        }
+        top += 1;
+        left += 1;
+        bottom -= 1;
+        right -= 1;
+    }
+    cells
+}
+
+impl Cipher for Route {
+    fn encrypt(&self, plain: &str) -> String {
+        gather(plain, |len| self.positions(len))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        scatter(cipher_text, |len| self.positions(len))
+    }
+}
+
+/// Try every path for every number of the columns up to the `max_columns`,
+/// the best scored decryptions first
+pub fn crack_route<S>(cipher_text: &str, max_columns: usize, scorer: &S) -> Vec<(Route, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let len = cipher_text.chars().count();
+    let candidates = (1..=max_columns.min(len))
+        .cartesian_product(Path::ALL.iter().copied())
+        .map(|(columns, path)| Route { columns, path })
+        .collect();
+    rank_candidates(cipher_text, candidates, scorer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::NGrams;
+
+    use super::*;
+
+    const PLAIN: &str = "we are discovered, flee at once and take the maps with you";
+
+    #[test]
+    fn columnar_with_keyword() {
+        let zebras = Columnar::from_keyword("ZEBRAS").unwrap();
+        assert_eq!(zebras.order(), &[4, 2, 1, 3, 5, 0]);
+        assert_eq!(
+            zebras.encrypt("WEAREDISCOVEREDFLEEATONCE"),
+            "EVLNACDTESEAROFODEECWIREE"
+        );
+        assert_eq!(
+            zebras.decrypt("EVLNACDTESEAROFODEECWIREE"),
+            "WEAREDISCOVEREDFLEEATONCE"
+        );
+    }
+
+    #[test]
+    fn columnar_bad_order() {
+        assert!(Columnar::new(vec![]).is_err());
+        assert!(Columnar::new(vec![0, 2]).is_err());
+        assert!(Columnar::new(vec![1, 1]).is_err());
+        assert!(Columnar::from_keyword("").is_err());
+    }
+
+    #[test]
+    fn rail_fence() {
+        let fence = RailFence::new(3).unwrap();
+        assert_eq!(
+            fence.encrypt("WEAREDISCOVEREDFLEEATONCE"),
+            "WECRLTEERDSOEEFEAOCAIVDEN"
+        );
+        assert_eq!(fence.decrypt(&fence.encrypt(PLAIN)), PLAIN);
+        assert!(RailFence::new(1).is_err());
+    }
+
+    #[test]
+    fn route_paths() {
+        // abc
+        // def
+        // gh
+        let spiral = Route::new(3, Path::Spiral).unwrap();
+        assert_eq!(spiral.encrypt("abcdefgh"), "abcfhgde");
+        let snake = Route::new(3, Path::Snake).unwrap();
+        assert_eq!(snake.encrypt("abcdefgh"), "adghebcf");
+
+        for columns in 1..10 {
+            for &path in &Path::ALL {
+                let route = Route::new(columns, path).unwrap();
+                assert_eq!(route.decrypt(&route.encrypt(PLAIN)), PLAIN);
+            }
+        }
+        assert!(Route::new(0, Path::Spiral).is_err());
+    }
+
+    #[test]
+    fn crack_the_columns_order() {
+        let columnar = Columnar::new(vec![2, 0, 4, 1, 3]).unwrap();
+        let cipher_text = columnar.encrypt(PLAIN);
+
+        let candidates = crack_columnar(&cipher_text, 6, NGrams::english_quadgrams());
+        assert_eq!(candidates[0].0, columnar);
+        assert_eq!(candidates[0].0.decrypt(&cipher_text), PLAIN);
+    }
+
+    #[test]
+    fn crack_the_rails() {
+        let fence = RailFence::new(4).unwrap();
+        let cipher_text = fence.encrypt(PLAIN);
+
+        let candidates = crack_rail_fence(&cipher_text, 10, NGrams::english_quadgrams());
+        assert_eq!(candidates[0].0, fence);
+    }
+
+    #[test]
+    fn crack_the_route() {
+        let route = Route::new(7, Path::Spiral).unwrap();
+        let cipher_text = route.encrypt(PLAIN);
+
+        let candidates = crack_route(&cipher_text, 12, NGrams::english_quadgrams());
+        assert_eq!(candidates[0].0.decrypt(&cipher_text), PLAIN);
+    }
+}
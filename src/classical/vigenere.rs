@@ -0,0 +1,173 @@
+//! Vigenère cipher: the letters are shifted by the letters of the repeating key.
+//!
+//! Only the letters of the text consume the key, so the spaces
+//! and punctuation do not break the columns of the same shift.
+
+use crate::{
+    score::{ChiSquared, Scorer},
+    Error, Result,
+};
+
+use super::{caesar::Caesar, letter_indices, map_letters, Cipher, ALPHABET_SIZE};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vigenere {
+    shifts: Vec<u8>,
+}
+
+impl Vigenere {
+    /// # Errors
+    /// The key is empty or has non-letters
+    pub fn new(key: &str) -> Result<Self> {
+        if key.is_empty() || !key.bytes().all(|byte| byte.is_ascii_alphabetic()) {
+            return Err(Error::BadKey("the Vigenère key should consist of letters"));
+        }
+
+        Ok(Self {
+            shifts: letter_indices(key),
+        })
+    }
+
+    /// The uppercase key
+    pub fn key(&self) -> String {
+        self.shifts
+            .iter()
+            .map(|shift| char::from(b'A' + shift))
+            .collect()
+    }
+
+    fn apply(&self, text: &str, decrypt: bool) -> String {
+        let mut shifts = self.shifts.iter().cycle();
+        map_letters(text, |i| {
+            let shift = shifts.next().copied().unwrap_or(0);
+            if decrypt {
+                i + ALPHABET_SIZE - shift
+            } else {
+                i + shift
+            }
+        })
+    }
+}
+
+impl Cipher for Vigenere {
+    fn encrypt(&self, plain: &str) -> String {
+        self.apply(plain, false)
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.apply(cipher_text, true)
+    }
+}
+
+/// The number of the most probable key sizes to try breaking
+const KEY_SIZE_CANDIDATES: usize = 5;
+
+/// Guess the key size by the index of coincidence of the letters,
+/// then break every column as the Caesar cipher by the letters frequencies.
+///
+/// Returns the keys of the most probable sizes (up to the `max_key_size`),
+/// the best scored decryptions first.
+pub fn crack<S>(cipher_text: &str, max_key_size: usize, scorer: &S) -> Vec<(Vigenere, u64)>
+where
+    S: Scorer + ?Sized,
+{
+    let letters = letter_indices(cipher_text);
+
+    let mut key_sizes: Vec<_> = (1..=max_key_size)
+        .filter(|&key_size| letters.len() >= key_size * 2)
+        .map(|key_size| (key_size, average_index_of_coincidence(&letters, key_size)))
+        .collect();
+    key_sizes.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let mut candidates: Vec<_> = key_sizes
+        .into_iter()
+        .take(KEY_SIZE_CANDIDATES)
+        .map(|(key_size, _)| {
+            let shifts = (0..key_size)
+                .map(|index| {
+                    let column: Vec<_> = letters.iter().skip(index).step_by(key_size).collect();
+                    column_shift(&column)
+                })
+                .collect();
+            let vigenere = Vigenere { shifts };
+            let score = scorer.score(&vigenere.decrypt(cipher_text));
+            (vigenere, score)
+        })
+        .collect();
+
+    // the multiples of the right key decrypt the same, prefer the shortest one
+    candidates.sort_by_key(|(vigenere, score)| (*score, vigenere.shifts.len()));
+    candidates
+}
+
+fn average_index_of_coincidence(letters: &[u8], key_size: usize) -> f64 {
+    #![allow(clippy::cast_precision_loss)]
+
+    let total: f64 = (0..key_size)
+        .map(|index| {
+            let mut counts = [0_usize; ALPHABET_SIZE as usize];
+            let mut len = 0_usize;
+            for &letter in letters.iter().skip(index).step_by(key_size) {
+                counts[usize::from(letter)] += 1;
+                len += 1;
+            }
+
+            if len < 2 {
+                return 0.0;
+            }
+            let coincidences: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+            coincidences as f64 / (len * (len - 1)) as f64
+        })
+        .sum();
+
+    total / key_size as f64
+}
+
+/// The Caesar shift of the column making it the most English-like
+fn column_shift(column: &[&u8]) -> u8 {
+    let text: String = column
+        .iter()
+        .map(|&&letter| char::from(b'a' + letter))
+        .collect();
+
+    (0..ALPHABET_SIZE)
+        .min_by_key(|&shift| ChiSquared.score(&Caesar::new(shift).decrypt(&text)))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::score::NGrams;
+
+    use super::*;
+
+    const PLAIN: &str = "It was the best of times, it was the worst of times, \
+        it was the age of wisdom, it was the age of foolishness, \
+        it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of Light, it was the season of Darkness, \
+        it was the spring of hope, it was the winter of despair.";
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let vigenere = Vigenere::new("lemon").unwrap();
+        assert_eq!(vigenere.key(), "LEMON");
+        assert_eq!(vigenere.encrypt("Attack at dawn!"), "Lxfopv ef rnhr!");
+        assert_eq!(vigenere.decrypt("Lxfopv ef rnhr!"), "Attack at dawn!");
+    }
+
+    #[test]
+    fn bad_key() {
+        assert!(Vigenere::new("").is_err());
+        assert!(Vigenere::new("ice ice").is_err());
+    }
+
+    #[test]
+    fn crack_the_key() {
+        let vigenere = Vigenere::new("Dickens").unwrap();
+        let cipher_text = vigenere.encrypt(PLAIN);
+
+        let candidates = crack(&cipher_text, 20, NGrams::english_quadgrams());
+        assert_eq!(candidates[0].0, vigenere);
+        assert_eq!(candidates[0].0.decrypt(&cipher_text), PLAIN);
+    }
+}
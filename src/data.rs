@@ -0,0 +1,307 @@
+//! The input files of the challenges.
+//!
+//! The files are looked up in the `data` directory of the crate
+//! (no matter where the binary is run from) or in the directory
+//! given by the `PALS_DATA_DIR` environment variable.
+//! Every file is checked against its known checksum before decoding.
+
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{hex, Error, Result};
+
+/// The environment variable to override the [`data_dir`]
+pub const DATA_DIR_VAR: &str = "PALS_DATA_DIR";
+
+/// The way the binary data is stored in the text file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Every line is a separate hex-encoded item
+    HexLines,
+    /// Every line is a separate base64-encoded item
+    Base64Lines,
+    /// The whole file is a single base64-encoded item wrapped into lines
+    Base64,
+}
+
+impl Format {
+    /// Decode every item of the file
+    ///
+    /// # Errors
+    /// Some item is not a valid hex or base64
+    pub fn decode(self, text: &str) -> Result<Vec<Vec<u8>>> {
+        let lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        match self {
+            Self::HexLines => lines.map(hex::decode).collect(),
+            Self::Base64Lines => lines
+                .map(|line| base64::decode(line).map_err(Error::from))
+                .collect(),
+            Self::Base64 => Ok(vec![base64::decode(lines.collect::<String>())?]),
+        }
+    }
+}
+
+/// The input file of the challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixture {
+    pub challenge: u8,
+    pub format: Format,
+    /// The POSIX `cksum` of the original file.
+    /// The file is rejected if unknown.
+    checksum: Option<u32>,
+}
+
+/// All the challenges with the input files.
+///
+/// The checksums of the files are to be filled in with the `cksum data/N.txt`
+/// once the files are checked out, until then the files are rejected.
+pub const FIXTURES: [Fixture; 6] = [
+    Fixture::new(4, Format::HexLines, None),
+    Fixture::new(6, Format::Base64, None),
+    Fixture::new(7, Format::Base64, None),
+    Fixture::new(8, Format::HexLines, None),
+    Fixture::new(10, Format::Base64, None),
+    Fixture::new(20, Format::Base64Lines, None),
+];
+
+impl Fixture {
+    pub const fn new(challenge: u8, format: Format, checksum: Option<u32>) -> Self {
+        Self {
+            challenge,
+            format,
+            checksum,
+        }
+    }
+
+    /// The fixture of the challenge if it has one
+    pub fn of(challenge: u8) -> Option<&'static Self> {
+        FIXTURES
+            .iter()
+            .find(|fixture| fixture.challenge == challenge)
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.txt", self.challenge)
+    }
+
+    /// Read the file from the directory and decode every item
+    ///
+    /// # Errors
+    /// - the file cannot be read
+    /// - the file does not match the checksum or cannot be decoded
+    ///   (the [`io::ErrorKind::InvalidData`] with the [`Error`] inside)
+    pub fn read_from(&self, dir: &Path) -> io::Result<Vec<Vec<u8>>> {
+        let path = dir.join(self.file_name());
+        let bytes = fs::read(&path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("Cannot read {}: {}", path.display(), err),
+            )
+        })?;
+        self.parse(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, FixtureError { path, err }))
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let actual = cksum(bytes);
+        let expected = self.checksum.ok_or(Error::UnknownChecksum { actual })?;
+        if actual != expected {
+            return Err(Error::ChecksumMismatch { expected, actual });
+        }
+
+        let text = String::from_utf8(bytes.to_vec())?;
+        self.format.decode(&text)
+    }
+}
+
+/// The error of the fixture along with the path to it
+#[derive(Debug)]
+struct FixtureError {
+    path: PathBuf,
+    err: Error,
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.err)
+    }
+}
+
+impl std::error::Error for FixtureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+/// The directory with the input files
+pub fn data_dir() -> PathBuf {
+    env::var_os(DATA_DIR_VAR).map_or_else(
+        || Path::new(env!("CARGO_MANIFEST_DIR")).join("data"),
+        PathBuf::from,
+    )
+}
+
+fn fixture(challenge: u8) -> io::Result<&'static Fixture> {
+    Fixture::of(challenge).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("The challenge {} has no input file", challenge),
+        )
+    })
+}
+
+/// The decoded items of the challenge input, one per line
+/// (or the single one for the [`Format::Base64`]).
+///
+/// # Errors
+/// See the [`Fixture::read_from`]. The [`io::ErrorKind::NotFound`]
+/// is also returned if the challenge has no input file at all.
+pub fn load_lines(challenge: u8) -> io::Result<Vec<Vec<u8>>> {
+    fixture(challenge)?.read_from(&data_dir())
+}
+
+/// The whole decoded input of the challenge.
+/// The items of the line-by-line formats are concatenated.
+///
+/// # Errors
+/// See the [`load_lines`]
+pub fn load(challenge: u8) -> io::Result<Vec<u8>> {
+    Ok(load_lines(challenge)?.concat())
+}
+
+/// The CRC-32 checksum as calculated by the POSIX `cksum` utility
+pub fn cksum(data: &[u8]) -> u32 {
+    const POLYNOMIAL: u32 = 0x04c1_1db7;
+
+    fn update(crc: u32, byte: u8) -> u32 {
+        let mut crc = crc ^ (u32::from(byte) << 24);
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ POLYNOMIAL
+            };
+        }
+        crc
+    }
+
+    let mut crc = data.iter().fold(0, |crc, &byte| update(crc, byte));
+
+    // the length is appended with the least significant bytes first,
+    // without the trailing zero bytes
+    let mut len = data.len();
+    while len > 0 {
+        #[allow(clippy::cast_possible_truncation)]
+        let byte = len as u8;
+        crc = update(crc, byte);
+        len >>= 8;
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posix_checksum() {
+        // the output of the `printf ... | cksum`
+        assert_eq!(cksum(b""), 4_294_967_295);
+        assert_eq!(cksum(b"123456789"), 930_766_865);
+        assert_eq!(cksum(&[b'a'; 300]), cksum(&[b'a'; 300]));
+        assert_ne!(cksum(&[b'a'; 300]), cksum(&[b'a'; 301]));
+    }
+
+    #[test]
+    fn decode_the_formats() {
+        assert_eq!(
+            Format::HexLines.decode("0102\n\nff\n").unwrap(),
+            [vec![1, 2], vec![255]]
+        );
+        assert_eq!(
+            Format::Base64Lines
+                .decode("SUNF\r\nSUNFIEJBQlk=\n")
+                .unwrap(),
+            [b"ICE".to_vec(), b"ICE BABY".to_vec()]
+        );
+        assert_eq!(
+            Format::Base64.decode("SUNFIElD\nRSBCQUJZ\n").unwrap(),
+            [b"ICE ICE BABY".to_vec()]
+        );
+        assert!(Format::HexLines.decode("0102\nxyz\n").is_err());
+    }
+
+    #[test]
+    fn verify_the_checksum() {
+        let text = b"SUNFIElD\nRSBCQUJZ\n";
+        let fixture = Fixture::new(6, Format::Base64, Some(cksum(text)));
+        assert_eq!(fixture.parse(text).unwrap(), [b"ICE ICE BABY".to_vec()]);
+
+        let corrupted = b"SUNFIElD\nRSBCQUJa\n";
+        assert!(matches!(
+            fixture.parse(corrupted),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        // no checksum to verify
+        let fixture = Fixture::new(6, Format::Base64, None);
+        assert_eq!(
+            fixture.parse(text),
+            Err(Error::UnknownChecksum {
+                actual: cksum(text)
+            })
+        );
+    }
+
+    #[test]
+    fn read_the_file() {
+        let dir = env::temp_dir().join(format!("pals-data-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let text = "00ff\n0a0b\n";
+        fs::write(dir.join("8.txt"), text).unwrap();
+
+        assert_eq!(Fixture::of(8).unwrap().format, Format::HexLines);
+        let fixture = Fixture::new(8, Format::HexLines, Some(cksum(text.as_bytes())));
+        assert_eq!(
+            fixture.read_from(&dir).unwrap(),
+            [vec![0, 255], vec![10, 11]]
+        );
+
+        let text = "00fg\n";
+        fs::write(dir.join("8.txt"), text).unwrap();
+        let fixture = Fixture::new(8, Format::HexLines, Some(cksum(text.as_bytes())));
+        let err = fixture.read_from(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("8.txt"));
+
+        let err = Fixture::of(4).unwrap().read_from(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[ignore = "the data/N.txt files with their checksums are not checked in yet"]
+    fn load_every_fixture() {
+        for fixture in &FIXTURES {
+            let items = load_lines(fixture.challenge).unwrap();
+            assert!(!items.is_empty());
+            assert!(items.iter().all(|item| !item.is_empty()));
+        }
+    }
+
+    #[test]
+    fn no_fixture() {
+        assert!(Fixture::of(5).is_none());
+        assert_eq!(load(5).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn default_data_dir() {
+        if env::var_os(DATA_DIR_VAR).is_none() {
+            assert!(data_dir().ends_with("data"));
+            assert!(data_dir().is_absolute());
+        }
+    }
+}
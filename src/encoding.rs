@@ -0,0 +1,530 @@
+//! Text representations of the binary data used across the challenges.
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use super::{hex, Error, Result};
+
+/// Line width of the base64 in the MIME messages
+pub const MIME_LINE_WIDTH: usize = 76;
+/// Line width of the base64 in the PEM files
+pub const PEM_LINE_WIDTH: usize = 64;
+
+/// All the supported text encodings.
+///
+/// Decoding ignores any whitespace between the symbols,
+/// so the wrapped or pasted text can be decoded as is.
+/// The only exception is the percent-encoding, where the whitespace is a part of data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    /// Standard alphabet with the padding
+    Base64,
+    /// URL and filename safe alphabet without the padding
+    Base64Url,
+    /// RFC 4648 alphabet with the padding
+    Base32,
+    /// Adobe flavour of the base85 with the optional `<~` and `~>` delimiters
+    Ascii85,
+    /// URL-encoding of everything except the unreserved symbols
+    Percent,
+}
+
+impl Encoding {
+    pub const ALL: [Self; 6] = [
+        Self::Hex,
+        Self::Base64,
+        Self::Base64Url,
+        Self::Base32,
+        Self::Ascii85,
+        Self::Percent,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::Base64Url => "base64url",
+            Self::Base32 => "base32",
+            Self::Ascii85 => "ascii85",
+            Self::Percent => "percent",
+        }
+    }
+
+    pub fn encode(self, data: &[u8]) -> String {
+        match self {
+            Self::Hex => hex::encode(data),
+            Self::Base64 => base64::encode(data),
+            Self::Base64Url => base64::encode_config(data, base64::URL_SAFE_NO_PAD),
+            Self::Base32 => base32_encode(data),
+            Self::Ascii85 => ascii85_encode(data),
+            Self::Percent => percent_encode(data),
+        }
+    }
+
+    /// # Errors
+    /// The text is not a valid representation in the given encoding
+    pub fn decode(self, s: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Hex => hex::decode_lenient(s),
+            Self::Base64 => Ok(base64::decode(without_whitespace(s))?),
+            Self::Base64Url => {
+                let s = without_whitespace(s);
+                Ok(base64::decode_config(
+                    s.trim_end_matches('='),
+                    base64::URL_SAFE_NO_PAD,
+                )?)
+            }
+            Self::Base32 => base32_decode(s),
+            Self::Ascii85 => ascii85_decode(s),
+            Self::Percent => percent_decode(s),
+        }
+    }
+
+    /// Guess the encoding of the pasted text.
+    ///
+    /// The candidates are checked from the most specific alphabet to the least one,
+    /// so the text which is valid in several encodings (e.g. `"1234"` is both a hex and a base64)
+    /// is recognized as the first of them. The ascii85 is only detected with the delimiters.
+    pub fn detect(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
+        }
+
+        let symbols = without_whitespace(s);
+        let matches_alphabet = |encoding| match encoding {
+            Self::Ascii85 => s.starts_with("<~") && s.ends_with("~>"),
+            Self::Hex => symbols.bytes().all(|b| b.is_ascii_hexdigit()),
+            Self::Percent => s.contains('%'),
+            Self::Base32 => {
+                symbols.len() % 8 == 0
+                    && symbols
+                        .bytes()
+                        .all(|b| matches!(b, b'A'..=b'Z' | b'2'..=b'7' | b'='))
+            }
+            Self::Base64Url => symbols.contains(&['-', '_'][..]),
+            Self::Base64 => true,
+        };
+
+        [
+            Self::Ascii85,
+            Self::Hex,
+            Self::Percent,
+            Self::Base32,
+            Self::Base64Url,
+            Self::Base64,
+        ]
+        .iter()
+        .copied()
+        .find(|&encoding| matches_alphabet(encoding) && encoding.decode(s).is_ok())
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownEncoding(s.to_string()))
+    }
+}
+
+/// Split the encoded text into the lines of the given width (e.g. [`MIME_LINE_WIDTH`]).
+/// The zero width leaves the text as is.
+pub fn wrap(encoded: &str, width: usize) -> String {
+    if width == 0 {
+        return encoded.to_string();
+    }
+
+    let mut result = String::with_capacity(encoded.len() + encoded.len() / width);
+    for (i, ch) in encoded.chars().enumerate() {
+        if i > 0 && i % width == 0 {
+            result.push('\n');
+        }
+        result.push(ch);
+    }
+    result
+}
+
+fn without_whitespace(s: &str) -> String {
+    s.chars().filter(|ch| !ch.is_ascii_whitespace()).collect()
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity((data.len() + 4) / 5 * 8);
+    for chunk in data.chunks(5) {
+        let mut group = [0; 5];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let group = group.iter().fold(0_u64, |acc, &b| acc << 8 | u64::from(b));
+
+        let significant = (chunk.len() * 8 + 4) / 5;
+        for i in 0..8 {
+            if i < significant {
+                let index = (group >> (35 - 5 * i)) & 0x1f;
+                result.push(BASE32_ALPHABET[index as usize].into());
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+fn base32_decode(s: &str) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len() * 5 / 8);
+    let mut bits = 0_u16;
+    let mut bits_count = 0;
+    let mut symbols = 0;
+    let mut last_symbol = 0;
+    let mut padding_start = None;
+    let mut padding = 0;
+
+    for (position, &symbol) in s.as_bytes().iter().enumerate() {
+        if symbol.is_ascii_whitespace() {
+            continue;
+        }
+        if symbol == b'=' {
+            padding_start.get_or_insert(position);
+            padding += 1;
+            continue;
+        }
+
+        let value = match symbol {
+            _ if padding_start.is_some() => None,
+            b'A'..=b'Z' => Some(symbol - b'A'),
+            b'a'..=b'z' => Some(symbol - b'a'),
+            b'2'..=b'7' => Some(symbol - b'2' + 26),
+            _ => None,
+        }
+        .ok_or(Error::InvalidBase32 { position })?;
+
+        symbols += 1;
+        last_symbol = position;
+        bits = bits << 5 | u16::from(value);
+        bits_count += 5;
+        if bits_count >= 8 {
+            bits_count -= 8;
+            #[allow(clippy::cast_possible_truncation)]
+            result.push((bits >> bits_count) as u8);
+            bits &= (1 << bits_count) - 1;
+        }
+    }
+
+    // 1, 3 or 6 trailing symbols cannot encode a whole number of bytes
+    if bits_count >= 5 {
+        return Err(Error::InvalidBase32 { position: s.len() });
+    }
+    // the padding is optional, but should complete the last group of 8 symbols
+    if let Some(position) = padding_start {
+        if (symbols + padding) % 8 != 0 || padding >= 8 {
+            return Err(Error::InvalidBase32 { position });
+        }
+    }
+    // the canonical encoding has all the unused bits of the last symbol unset
+    if bits != 0 {
+        return Err(Error::InvalidBase32 {
+            position: last_symbol,
+        });
+    }
+
+    Ok(result)
+}
+
+const ASCII85_ZERO_GROUP: char = 'z';
+
+fn ascii85_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity((data.len() + 3) / 4 * 5);
+    for chunk in data.chunks(4) {
+        if chunk == [0; 4] {
+            result.push(ASCII85_ZERO_GROUP);
+            continue;
+        }
+
+        let mut group = [0; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(group);
+
+        let mut digits = [0; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 85) as u8 + b'!';
+            value /= 85;
+        }
+        result.extend(digits[..=chunk.len()].iter().map(|&d| char::from(d)));
+    }
+    result
+}
+
+fn ascii85_decode(s: &str) -> Result<Vec<u8>> {
+    let mut start = s.len() - s.trim_start().len();
+    let mut end = start + s.trim().len();
+    if s[start..end].starts_with("<~") {
+        start += 2;
+    }
+    if s[start..end].ends_with("~>") {
+        end -= 2;
+    }
+
+    let mut result = Vec::with_capacity((end - start) * 4 / 5);
+    let mut group = [0_u8; 5];
+    let mut group_len = 0;
+
+    let push_group = |result: &mut Vec<u8>, group: &[u8; 5], position| -> Result<()> {
+        let value = group
+            .iter()
+            .fold(0_u64, |acc, &digit| acc * 85 + u64::from(digit));
+        let value = u32::try_from(value).map_err(|_| Error::InvalidAscii85 { position })?;
+        result.extend_from_slice(&value.to_be_bytes());
+        Ok(())
+    };
+
+    for (position, &symbol) in s.as_bytes()[start..end].iter().enumerate() {
+        let position = start + position;
+        match symbol {
+            _ if symbol.is_ascii_whitespace() => {}
+            b'z' if group_len == 0 => result.extend_from_slice(&[0; 4]),
+            b'!'..=b'u' => {
+                group[group_len] = symbol - b'!';
+                group_len += 1;
+                if group_len == group.len() {
+                    push_group(&mut result, &group, position)?;
+                    group_len = 0;
+                }
+            }
+            _ => return Err(Error::InvalidAscii85 { position }),
+        }
+    }
+
+    match group_len {
+        0 => {}
+        1 => return Err(Error::InvalidAscii85 { position: end }),
+        _ => {
+            // pad the partial group with the highest digit and drop the extra bytes
+            let significant = group_len - 1;
+            group[group_len..].iter_mut().for_each(|digit| *digit = 84);
+            push_group(&mut result, &group, end)?;
+            result.truncate(result.len() - (4 - significant));
+        }
+    }
+
+    Ok(result)
+}
+
+const fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn percent_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len());
+    for &byte in data {
+        if is_unreserved(byte) {
+            result.push(byte.into());
+        } else {
+            result.push('%');
+            result.push_str(&hex::encode_upper(&[byte]));
+        }
+    }
+    result
+}
+
+fn percent_decode(s: &str) -> Result<Vec<u8>> {
+    let bytes = s.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+
+    let mut position = 0;
+    while position < bytes.len() {
+        if bytes[position] == b'%' {
+            let byte = s
+                .get(position + 1..position + 3)
+                .and_then(|digits| hex::decode(digits).ok())
+                .ok_or(Error::InvalidPercent { position })?;
+            result.extend(byte);
+            position += 3;
+        } else {
+            result.push(bytes[position]);
+            position += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base32_rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+
+        for (plain, encoded) in &vectors {
+            assert_eq!(Encoding::Base32.encode(plain.as_bytes()), *encoded);
+            assert_eq!(Encoding::Base32.decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn base32_errors() {
+        assert_eq!(
+            Encoding::Base32.decode("MZXW1==="),
+            Err(Error::InvalidBase32 { position: 4 })
+        );
+        assert_eq!(
+            Encoding::Base32.decode("MY==MY=="),
+            Err(Error::InvalidBase32 { position: 4 })
+        );
+        assert_eq!(
+            Encoding::Base32.decode("MZX"),
+            Err(Error::InvalidBase32 { position: 3 })
+        );
+        // the padding does not complete the group
+        assert_eq!(
+            Encoding::Base32.decode("MY="),
+            Err(Error::InvalidBase32 { position: 2 })
+        );
+        assert_eq!(
+            Encoding::Base32.decode("MZXW6YTB========"),
+            Err(Error::InvalidBase32 { position: 8 })
+        );
+        // the unused bits of the last symbol are set
+        assert_eq!(
+            Encoding::Base32.decode("MZ======"),
+            Err(Error::InvalidBase32 { position: 1 })
+        );
+        // no padding at all is fine
+        assert_eq!(Encoding::Base32.decode("MZXW6").unwrap(), b"foo");
+    }
+
+    #[test]
+    fn ascii85_known_values() {
+        assert_eq!(Encoding::Ascii85.encode(b"Man "), "9jqo^");
+        assert_eq!(Encoding::Ascii85.encode(b"sure."), "F*2M7/c");
+        assert_eq!(Encoding::Ascii85.encode(&[0; 9]), "zz!!");
+
+        assert_eq!(
+            Encoding::Ascii85.decode("<~9jqo^F*2M7/c~>").unwrap(),
+            b"Man sure."
+        );
+        assert_eq!(Encoding::Ascii85.decode(" z\nz !! ").unwrap(), vec![0; 9]);
+    }
+
+    #[test]
+    fn ascii85_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        for len in 0..20 {
+            let encoded = Encoding::Ascii85.encode(&data[..len]);
+            assert_eq!(Encoding::Ascii85.decode(&encoded).unwrap(), &data[..len]);
+        }
+    }
+
+    #[test]
+    fn ascii85_errors() {
+        assert_eq!(
+            Encoding::Ascii85.decode("<~9jqo^F~>"),
+            Err(Error::InvalidAscii85 { position: 8 })
+        );
+        assert_eq!(
+            Encoding::Ascii85.decode("9jzo^"),
+            Err(Error::InvalidAscii85 { position: 2 })
+        );
+        assert_eq!(
+            Encoding::Ascii85.decode("uuuuu"),
+            Err(Error::InvalidAscii85 { position: 4 })
+        );
+    }
+
+    #[test]
+    fn percent_encoding_of_the_comments() {
+        assert_eq!(
+            Encoding::Percent.encode(b" like a pound of bacon"),
+            "%20like%20a%20pound%20of%20bacon"
+        );
+        assert_eq!(Encoding::Percent.encode(b";admin=true"), "%3Badmin%3Dtrue");
+        assert_eq!(
+            Encoding::Percent.decode("cooking%20MCs").unwrap(),
+            b"cooking MCs"
+        );
+        assert_eq!(
+            Encoding::Percent.decode("cooking%2MCs"),
+            Err(Error::InvalidPercent { position: 7 })
+        );
+        assert_eq!(
+            Encoding::Percent.decode("100%"),
+            Err(Error::InvalidPercent { position: 3 })
+        );
+    }
+
+    #[test]
+    fn base64_variants() {
+        let data = [0xfb, 0xff, 0x00, 0x3e];
+        assert_eq!(Encoding::Base64.encode(&data), "+/8APg==");
+        assert_eq!(Encoding::Base64Url.encode(&data), "-_8APg");
+        assert_eq!(Encoding::Base64Url.decode("-_8APg==").unwrap(), data);
+        assert_eq!(Encoding::Base64.decode("+/8A\nPg==\n").unwrap(), data);
+    }
+
+    #[test]
+    fn wrapped_base64() {
+        let data = vec![0; 100];
+        let wrapped = wrap(&Encoding::Base64.encode(&data), PEM_LINE_WIDTH);
+        let lines: Vec<_> = wrapped.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), PEM_LINE_WIDTH);
+        assert_eq!(lines[1].len(), PEM_LINE_WIDTH);
+        assert_eq!(lines[2].len(), 8);
+
+        assert_eq!(Encoding::Base64.decode(&wrapped).unwrap(), data);
+
+        assert_eq!(wrap("TWFu", 0), "TWFu");
+        assert_eq!(wrap("TWFu", 3), "TWF\nu");
+    }
+
+    #[test]
+    fn detect_the_encoding() {
+        let data = b"Rollin' in my 5.0\xff";
+        for &encoding in &Encoding::ALL {
+            let mut encoded = encoding.encode(data);
+            if encoding == Encoding::Ascii85 {
+                encoded = format!("<~{}~>", encoded);
+            }
+            assert_eq!(Encoding::detect(&encoded), Some(encoding), "{}", encoded);
+        }
+
+        assert_eq!(
+            Encoding::detect(&wrap(&Encoding::Base64.encode(&[7; 100]), MIME_LINE_WIDTH)),
+            Some(Encoding::Base64)
+        );
+        assert_eq!(Encoding::detect("  "), None);
+        assert_eq!(Encoding::detect("not encoded at all!"), None);
+    }
+
+    #[test]
+    fn parse_the_name() {
+        for &encoding in &Encoding::ALL {
+            assert_eq!(encoding.to_string().parse(), Ok(encoding));
+        }
+        assert_eq!("Base64".parse(), Ok(Encoding::Base64));
+        assert_eq!(
+            "rot13".parse::<Encoding>(),
+            Err(Error::UnknownEncoding("rot13".to_string()))
+        );
+    }
+}
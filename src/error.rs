@@ -0,0 +1,165 @@
+use std::{error, fmt, result, string::FromUtf8Error};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Non-hexadecimal symbol or a missing digit at the given position
+    InvalidHex {
+        position: usize,
+    },
+    InvalidBase64(base64::DecodeError),
+    /// Non-base32 symbol or a wrong number of symbols at the given position
+    InvalidBase32 {
+        position: usize,
+    },
+    /// Non-ascii85 symbol or an overflowing group at the given position
+    InvalidAscii85 {
+        position: usize,
+    },
+    /// Malformed percent-encoded byte at the given position
+    InvalidPercent {
+        position: usize,
+    },
+    /// The saved language model is malformed at the given position
+    InvalidModel {
+        position: usize,
+    },
+    /// The name of the encoding is not recognized
+    UnknownEncoding(String),
+    /// The code of the language is not recognized
+    UnknownLanguage(String),
+    /// The seed given in the environment is not a valid `u64`
+    InvalidSeed(String),
+    InvalidUtf8(FromUtf8Error),
+    InvalidPadding,
+    /// The AES key should be 16, 24 or 32 bytes long
+    BadKeyLength(usize),
+    /// The key of the classical cipher is not valid for the given reason
+    BadKey(&'static str),
+    /// The IV should be of the block size
+    BadIvLength(usize),
+    /// The data of the given size cannot be split into the whole blocks
+    PartialBlock {
+        size: usize,
+    },
+    /// None of the candidates satisfies the requirements
+    NoCandidate,
+    /// The size of the ciphertext does not reveal the block size
+    UnknownBlockSize,
+    /// The encryption oracle does not behave like the ECB mode
+    NotEcb,
+    /// The oracle has already answered the given number of queries
+    QueryBudgetExceeded(usize),
+    /// The query with the given number differs from the recorded one
+    ReplayMismatch {
+        query: usize,
+    },
+    /// The position is beyond the data of the given length
+    PositionOutOfRange {
+        position: usize,
+        len: usize,
+    },
+    /// The checksum of the file differs from the known one
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    /// The file has no known checksum to verify it against
+    UnknownChecksum {
+        actual: u32,
+    },
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex { position } => {
+                write!(f, "Invalid hexadecimal digit at position {}", position)
+            }
+            Self::InvalidBase64(err) => write!(f, "Invalid base64: {}", err),
+            Self::InvalidBase32 { position } => {
+                write!(f, "Invalid base32 symbol at position {}", position)
+            }
+            Self::InvalidAscii85 { position } => {
+                write!(f, "Invalid ascii85 symbol at position {}", position)
+            }
+            Self::InvalidPercent { position } => {
+                write!(f, "Invalid percent-encoded byte at position {}", position)
+            }
+            Self::InvalidModel { position } => {
+                write!(f, "Invalid language model at position {}", position)
+            }
+            Self::UnknownEncoding(name) => write!(f, "Unknown encoding {:?}", name),
+            Self::UnknownLanguage(code) => write!(f, "Unknown language {:?}", code),
+            Self::InvalidSeed(seed) => write!(
+                f,
+                "The {} should be an integer, got {:?}",
+                crate::random::SEED_VAR,
+                seed
+            ),
+            Self::InvalidUtf8(err) => write!(f, "Invalid UTF-8: {}", err),
+            Self::InvalidPadding => write!(f, "Invalid padding"),
+            Self::BadKeyLength(len) => write!(
+                f,
+                "The AES key should be 16, 24 or 32 bytes long, got {}",
+                len
+            ),
+            Self::BadKey(reason) => write!(f, "Bad key: {}", reason),
+            Self::BadIvLength(len) => write!(f, "The IV should be 16 bytes long, got {}", len),
+            Self::PartialBlock { size } => {
+                write!(f, "The data of size {} has an incomplete block", size)
+            }
+            Self::NoCandidate => write!(f, "No suitable candidate found"),
+            Self::UnknownBlockSize => write!(f, "Cannot detect the block size"),
+            Self::NotEcb => write!(f, "The oracle does not use the ECB mode"),
+            Self::QueryBudgetExceeded(budget) => {
+                write!(
+                    f,
+                    "The oracle refuses to answer more than {} queries",
+                    budget
+                )
+            }
+            Self::ReplayMismatch { query } => {
+                write!(f, "The query #{} does not match the transcript", query)
+            }
+            Self::PositionOutOfRange { position, len } => write!(
+                f,
+                "The position {} is out of range for the data of length {}",
+                position, len
+            ),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "The checksum {} does not match the expected {}",
+                actual, expected
+            ),
+            Self::UnknownChecksum { actual } => write!(
+                f,
+                "The checksum {} cannot be verified: no known one in the `pals::data::FIXTURES`",
+                actual
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidBase64(err) => Some(err),
+            Self::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Self::InvalidBase64(err)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Self {
+        Self::InvalidUtf8(err)
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::HashMap, convert::TryFrom, fmt, fs, hash::Hash, io, path::Path, str::FromStr,
+};
+
+use crate::{Error, Result};
 
 pub fn letters_frequencies(text: &str) -> HashMap<char, f64> {
     let valid_chars = text.chars().filter_map(|ch| {
@@ -62,3 +66,533 @@ const LETTER_FREQ: [(char, f64); 26] = [
 pub fn eng_map() -> HashMap<char, f64> {
     LETTER_FREQ.iter().copied().collect()
 }
+
+/// The pseudo-count of every byte value never seen in the text sample
+/// that usually appears in the texts: the printable ASCII and the common whitespaces
+const TEXT_BYTE_PRIOR: f64 = 1.0;
+/// The pseudo-count of every other byte value never seen in the text sample
+const BINARY_BYTE_PRIOR: f64 = 0.01;
+
+/// The frequencies of all the 256 byte values, including whitespaces,
+/// punctuation, control symbols and the non-ASCII bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteProfile {
+    frequencies: [f64; 256],
+}
+
+impl ByteProfile {
+    /// Normalize the given weights of the byte values
+    pub fn from_weights(weights: [f64; 256]) -> Self {
+        let total: f64 = weights.iter().sum();
+        let mut frequencies = weights;
+        if total > 0.0 {
+            for freq in &mut frequencies {
+                *freq /= total;
+            }
+        }
+        Self { frequencies }
+    }
+
+    /// Learn the frequencies of the binary data.
+    /// Every byte value gets one more occurrence, so no byte is impossible.
+    pub fn train(sample: &[u8]) -> Self {
+        Self::train_with_prior(sample, |_byte| 1.0)
+    }
+
+    /// Learn the frequencies of the text. The bytes never seen in the sample
+    /// are much less probable if they are not usual for the text.
+    pub fn train_text(sample: &str) -> Self {
+        Self::train_with_prior(sample.as_bytes(), |byte| {
+            if byte.is_ascii_graphic() || matches!(byte, b' ' | b'\t' | b'\n' | b'\r') {
+                TEXT_BYTE_PRIOR
+            } else {
+                BINARY_BYTE_PRIOR
+            }
+        })
+    }
+
+    fn train_with_prior(sample: &[u8], prior: impl Fn(u8) -> f64) -> Self {
+        let mut weights = [0.0; 256];
+        for byte in 0..=u8::MAX {
+            weights[usize::from(byte)] = prior(byte);
+        }
+        for &byte in sample {
+            weights[usize::from(byte)] += 1.0;
+        }
+        Self::from_weights(weights)
+    }
+
+    pub fn frequency(&self, byte: u8) -> f64 {
+        self.frequencies[usize::from(byte)]
+    }
+
+    /// The average number of bits per byte to encode the data
+    /// by the code optimal for this profile. The lower the closer.
+    pub fn cross_entropy(&self, data: &[u8]) -> f64 {
+        #![allow(clippy::cast_precision_loss)]
+
+        if data.is_empty() {
+            return 0.0;
+        }
+
+        let bits: f64 = data.iter().map(|&byte| -self.frequency(byte).log2()).sum();
+        bits / data.len() as f64
+    }
+}
+
+/// The texts to train the built-in models on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Self; 4] = [Self::English, Self::German, Self::French, Self::Russian];
+
+    /// ISO 639-1 code
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+            Self::French => "fr",
+            Self::Russian => "ru",
+        }
+    }
+
+    pub const fn corpus(self) -> &'static str {
+        match self {
+            Self::English => include_str!("corpus/en.txt"),
+            Self::German => include_str!("corpus/de.txt"),
+            Self::French => include_str!("corpus/fr.txt"),
+            Self::Russian => include_str!("corpus/ru.txt"),
+        }
+    }
+
+    /// The model of the n-grams trained on the built-in corpus
+    ///
+    /// # Panics
+    /// The `n` is zero
+    pub fn model(self, n: usize) -> NGramModel {
+        NGramModel::train(n, self.corpus())
+    }
+
+    /// The frequencies of the bytes of the built-in corpus in UTF-8
+    pub fn byte_profile(self) -> ByteProfile {
+        ByteProfile::train_text(self.corpus())
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|language| language.code().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownLanguage(s.to_string()))
+    }
+}
+
+/// The symbols the n-gram models are made of: the lowercase letters
+/// (of any alphabet), the digits and the punctuation signs as is,
+/// and a single space for every run of whitespaces.
+pub fn symbols(text: &str) -> Vec<char> {
+    let mut symbols: Vec<char> = Vec::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch.is_whitespace() {
+            if symbols.last() != Some(&' ') {
+                symbols.push(' ');
+            }
+        } else if ch.is_control() {
+            // not a part of any text
+            symbols.push('\u{fffd}');
+        } else {
+            symbols.extend(ch.to_lowercase());
+        }
+    }
+    symbols
+}
+
+/// The counts of the overlapping sequences of `n` symbols, see the [`symbols`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NGramModel {
+    n: usize,
+    counts: HashMap<Vec<char>, u64>,
+    total: u64,
+}
+
+/// The first bytes of the saved [`NGramModel`]
+const MODEL_MAGIC: &[u8] = b"NGRM\x01";
+
+impl NGramModel {
+    /// # Panics
+    /// The `n` is zero
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "The n-gram should have at least one symbol");
+        Self {
+            n,
+            counts: HashMap::new(),
+            total: 0,
+        }
+    }
+
+    /// # Panics
+    /// The `n` is zero
+    pub fn train(n: usize, text: &str) -> Self {
+        let mut model = Self::new(n);
+        model.update(text);
+        model
+    }
+
+    /// Train on the UTF-8 text file
+    ///
+    /// # Errors
+    /// The file cannot be read or is not a valid UTF-8
+    ///
+    /// # Panics
+    /// The `n` is zero
+    pub fn train_file(n: usize, path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Ok(Self::train(n, &text))
+    }
+
+    /// Count the n-grams of one more text
+    pub fn update(&mut self, text: &str) {
+        for gram in symbols(text).windows(self.n) {
+            *self.counts.entry(gram.to_vec()).or_insert(0) += 1;
+            self.total += 1;
+        }
+    }
+
+    pub const fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The number of all the n-grams seen
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn count(&self, gram: &[char]) -> u64 {
+        self.counts.get(gram).copied().unwrap_or(0)
+    }
+
+    pub fn frequency(&self, gram: &[char]) -> f64 {
+        #![allow(clippy::cast_precision_loss)]
+
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.count(gram) as f64 / self.total as f64
+    }
+
+    /// All the n-grams seen along with their counts, the most frequent first
+    pub fn most_frequent(&self) -> Vec<(&[char], u64)> {
+        let mut grams: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(gram, &count)| (gram.as_slice(), count))
+            .collect();
+        grams.sort_by(|(gram1, count1), (gram2, count2)| {
+            count2.cmp(count1).then_with(|| gram1.cmp(gram2))
+        });
+        grams
+    }
+
+    /// The compact binary representation:
+    /// the magic with the version, the `n` and the number of n-grams,
+    /// then every n-gram in UTF-8 prefixed with its length in bytes
+    /// followed by its count. All the numbers are LEB128 varints.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MODEL_MAGIC.to_vec();
+        write_varint(&mut bytes, self.n as u64);
+        write_varint(&mut bytes, self.counts.len() as u64);
+        for (gram, count) in self.most_frequent() {
+            let gram: String = gram.iter().collect();
+            write_varint(&mut bytes, gram.len() as u64);
+            bytes.extend_from_slice(gram.as_bytes());
+            write_varint(&mut bytes, count);
+        }
+        bytes
+    }
+
+    /// Parse the [`NGramModel::to_bytes`] representation
+    ///
+    /// # Errors
+    /// The data is truncated or malformed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(MODEL_MAGIC) {
+            return Err(Error::InvalidModel { position: 0 });
+        }
+
+        let mut reader = ByteReader {
+            bytes,
+            position: MODEL_MAGIC.len(),
+        };
+        let n = reader.read_usize()?;
+        if n == 0 {
+            return Err(Error::InvalidModel {
+                position: MODEL_MAGIC.len(),
+            });
+        }
+
+        let mut model = Self::new(n);
+        let grams = reader.read_usize()?;
+        for _ in 0..grams {
+            let position = reader.position;
+            let len = reader.read_usize()?;
+            let gram = std::str::from_utf8(reader.read_bytes(len)?)
+                .map_err(|_| Error::InvalidModel { position })?;
+            let gram: Vec<_> = gram.chars().collect();
+            if gram.len() != n {
+                return Err(Error::InvalidModel { position });
+            }
+
+            let count = reader.read_varint()?;
+            if model.counts.insert(gram, count).is_some() {
+                // the counts of the same n-gram are never split
+                return Err(Error::InvalidModel { position });
+            }
+            model.total = model
+                .total
+                .checked_add(count)
+                .ok_or(Error::InvalidModel { position })?;
+        }
+
+        if reader.position != bytes.len() {
+            return Err(Error::InvalidModel {
+                position: reader.position,
+            });
+        }
+        Ok(model)
+    }
+
+    /// # Errors
+    /// The file cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Read the model saved with the [`NGramModel::save`]
+    ///
+    /// # Errors
+    /// - the file cannot be read
+    /// - the file is not a valid model (the [`io::ErrorKind::InvalidData`]
+    ///   with the [`Error::InvalidModel`] inside)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    #![allow(clippy::cast_possible_truncation)]
+
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(Error::InvalidModel {
+                position: self.bytes.len(),
+            })?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let start = self.position;
+        let mut value = 0_u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(Error::InvalidModel { position: start })
+    }
+
+    fn read_usize(&mut self) -> Result<usize> {
+        let start = self.position;
+        let value = self.read_varint()?;
+        usize::try_from(value).map_err(|_| Error::InvalidModel { position: start })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_are_normalized() {
+        assert_eq!(
+            symbols("Hello,\n\t World!"),
+            "hello, world!".chars().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            symbols("Привет, МИР"),
+            "привет, мир".chars().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn count_the_ngrams() {
+        let model = NGramModel::train(2, "abab a");
+        assert_eq!(model.n(), 2);
+        // "ab", "ba", "ab", "b ", " a"
+        assert_eq!(model.total(), 5);
+        assert_eq!(model.count(&['a', 'b']), 2);
+        assert_eq!(model.count(&['b', ' ']), 1);
+        assert!((model.frequency(&['a', 'b']) - 0.4).abs() < f64::EPSILON);
+        assert_eq!(model.most_frequent()[0], (&['a', 'b'][..], 2));
+    }
+
+    #[test]
+    fn space_and_punctuation_are_counted() {
+        let model = Language::English.model(1);
+        let space = model.frequency(&[' ']);
+        let comma = model.frequency(&[',']);
+        let e = model.frequency(&['e']);
+        assert!(space > e, "{} vs {}", space, e);
+        assert!(comma > 0.0);
+        assert!(e > comma);
+    }
+
+    #[test]
+    fn built_in_languages_differ() {
+        let english = Language::English.model(1);
+        let german = Language::German.model(1);
+        let french = Language::French.model(1);
+        let russian = Language::Russian.model(1);
+
+        assert!(german.frequency(&['ü']) > 0.0);
+        assert_eq!(english.count(&['ü']), 0);
+        assert!(french.frequency(&['é']) > 0.0);
+        assert!(russian.frequency(&['о']) > russian.frequency(&['o']));
+        assert_eq!(russian.count(&['e']), 0);
+    }
+
+    #[test]
+    fn byte_profile_of_the_text() {
+        let english = Language::English.byte_profile();
+        let total: f64 = (0..=u8::MAX).map(|byte| english.frequency(byte)).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        assert!(english.frequency(b' ') > english.frequency(b'e'));
+        assert!(english.frequency(b'e') > english.frequency(b'E'));
+        // never seen in the corpus, but still much more usual than the control symbols
+        assert!(english.frequency(b'{') > english.frequency(0) * 10.0);
+        assert!(english.frequency(0) > 0.0);
+
+        let russian = Language::Russian.byte_profile();
+        // the most of the Cyrillic letters start with 0xD0 or 0xD1 in UTF-8
+        assert!(russian.frequency(0xd0) > russian.frequency(b'e'));
+    }
+
+    #[test]
+    fn byte_cross_entropy() {
+        let english = Language::English.byte_profile();
+        let text = "Cooking MC's like a pound of bacon";
+        let no_spaces = text.replace(' ', "\x00");
+        assert!(
+            english.cross_entropy(text.as_bytes()) < english.cross_entropy(no_spaces.as_bytes())
+        );
+        assert!(english.cross_entropy(b"").abs() < f64::EPSILON);
+
+        let binary = ByteProfile::train(&[0, 0, 0, 1]);
+        assert!(binary.frequency(0) > binary.frequency(1));
+        assert!(binary.frequency(2) > 0.0);
+    }
+
+    #[test]
+    fn language_codes() {
+        for language in &Language::ALL {
+            assert_eq!(language.to_string().parse::<Language>(), Ok(*language));
+        }
+        assert_eq!("RU".parse(), Ok(Language::Russian));
+        assert_eq!(
+            "xx".parse::<Language>(),
+            Err(Error::UnknownLanguage("xx".to_string()))
+        );
+    }
+
+    #[test]
+    fn save_and_load() {
+        let model = Language::Russian.model(4);
+        let bytes = model.to_bytes();
+        assert!(bytes.starts_with(MODEL_MAGIC));
+        assert_eq!(NGramModel::from_bytes(&bytes), Ok(model.clone()));
+
+        let path = std::env::temp_dir().join(format!("pals-ngrams-{}.bin", std::process::id()));
+        model.save(&path).unwrap();
+        let loaded = NGramModel::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, model);
+    }
+
+    #[test]
+    fn load_malformed() {
+        let bytes = NGramModel::train(2, "abc").to_bytes();
+        assert_eq!(
+            NGramModel::from_bytes(b"NGRX"),
+            Err(Error::InvalidModel { position: 0 })
+        );
+        assert_eq!(
+            NGramModel::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::InvalidModel {
+                position: bytes.len() - 1
+            })
+        );
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(NGramModel::from_bytes(&trailing).is_err());
+
+        let mut duplicate = MODEL_MAGIC.to_vec();
+        duplicate.extend_from_slice(&[2, 2, 2, b'a', b'a', 1, 2, b'a', b'a', 1]);
+        assert_eq!(
+            NGramModel::from_bytes(&duplicate),
+            Err(Error::InvalidModel {
+                position: MODEL_MAGIC.len() + 6
+            })
+        );
+
+        let huge_count = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        let mut overflow = MODEL_MAGIC.to_vec();
+        overflow.extend_from_slice(&[2, 2, 2, b'a', b'a']);
+        overflow.extend_from_slice(&huge_count);
+        overflow.extend_from_slice(&[2, b'a', b'b']);
+        overflow.extend_from_slice(&huge_count);
+        assert_eq!(
+            NGramModel::from_bytes(&overflow),
+            Err(Error::InvalidModel {
+                position: MODEL_MAGIC.len() + 15
+            })
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Hexadecimal representation of the binary data.
+//!
+//! Every byte is always encoded with exactly two digits,
+//! the decoding is strict by default and reports the position of the first bad symbol.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use super::{Error, Result};
+
+const LOWER_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const UPPER_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+fn encode_with(data: &[u8], digits: &[u8; 16]) -> String {
+    let mut result = String::with_capacity(data.len() * 2);
+    for byte in data {
+        result.push(digits[usize::from(byte >> 4)].into());
+        result.push(digits[usize::from(byte & 0xf)].into());
+    }
+    result
+}
+
+/// Two lowercase digits for every byte
+pub fn encode(data: &[u8]) -> String {
+    encode_with(data, LOWER_DIGITS)
+}
+
+/// Two uppercase digits for every byte
+pub fn encode_upper(data: &[u8]) -> String {
+    encode_with(data, UPPER_DIGITS)
+}
+
+const fn digit_value(symbol: u8) -> Option<u8> {
+    match symbol {
+        b'0'..=b'9' => Some(symbol - b'0'),
+        b'a'..=b'f' => Some(symbol - b'a' + 10),
+        b'A'..=b'F' => Some(symbol - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn decode_with(s: &str, skip_whitespace: bool) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len() / 2);
+    let mut high_digit = None;
+
+    for (position, &symbol) in s.as_bytes().iter().enumerate() {
+        if skip_whitespace && symbol.is_ascii_whitespace() {
+            continue;
+        }
+
+        let digit = digit_value(symbol).ok_or(Error::InvalidHex { position })?;
+        if let Some(high) = high_digit.take() {
+            result.push(high << 4 | digit);
+        } else {
+            high_digit = Some(digit);
+        }
+    }
+
+    if high_digit.is_some() {
+        return Err(Error::InvalidHex { position: s.len() });
+    }
+
+    Ok(result)
+}
+
+/// Only the hexadecimal digits allowed, in any case
+///
+/// # Errors
+/// - non-hexadecimal symbol found
+/// - odd number of hexadecimal digits
+pub fn decode(s: &str) -> Result<Vec<u8>> {
+    decode_with(s, false)
+}
+
+/// Same as the [`decode`] but ignores any whitespace (e.g. line breaks) between the digits.
+/// The error position still refers to the original string.
+///
+/// # Errors
+/// - non-hexadecimal symbol found
+/// - odd number of hexadecimal digits
+pub fn decode_lenient(s: &str) -> Result<Vec<u8>> {
+    decode_with(s, true)
+}
+
+/// Writes the hexadecimal representation of all the data written into it
+pub struct Encoder<W> {
+    inner: W,
+    digits: &'static [u8; 16],
+}
+
+impl<W: Write> Encoder<W> {
+    pub const fn new(inner: W) -> Self {
+        Self {
+            inner,
+            digits: LOWER_DIGITS,
+        }
+    }
+
+    pub const fn upper(inner: W) -> Self {
+        Self {
+            inner,
+            digits: UPPER_DIGITS,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner
+            .write_all(encode_with(buf, self.digits).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads the bytes from the hexadecimal representation.
+///
+/// The decoding errors are reported as [`io::ErrorKind::InvalidData`]
+/// with the [`Error::InvalidHex`] inside. The bytes decoded before the error
+/// are returned first, the error itself comes with the next read.
+pub struct Decoder<R> {
+    inner: BufReader<R>,
+    skip_whitespace: bool,
+    position: usize,
+    /// The error to report after the bytes decoded before it
+    error: Option<io::Error>,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: BufReader::new(inner),
+            skip_whitespace: false,
+            position: 0,
+            error: None,
+        }
+    }
+
+    /// Ignore any whitespace between the digits
+    pub fn lenient(inner: R) -> Self {
+        Self {
+            skip_whitespace: true,
+            ..Self::new(inner)
+        }
+    }
+
+    fn next_digit(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            let Some(&symbol) = self.inner.fill_buf()?.first() else {
+                return Ok(None);
+            };
+            self.inner.consume(1);
+
+            let position = self.position;
+            self.position += 1;
+            if self.skip_whitespace && symbol.is_ascii_whitespace() {
+                continue;
+            }
+
+            return digit_value(symbol)
+                .map(Some)
+                .ok_or_else(|| invalid_data(position));
+        }
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let Some(high) = self.next_digit()? else {
+            return Ok(None);
+        };
+        let low = self
+            .next_digit()?
+            .ok_or_else(|| invalid_data(self.position))?;
+
+        Ok(Some(high << 4 | low))
+    }
+}
+
+fn invalid_data(position: usize) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, Error::InvalidHex { position })
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        let mut written = 0;
+        for byte in buf.iter_mut() {
+            match self.next_byte() {
+                Ok(Some(decoded)) => *byte = decoded,
+                Ok(None) => break,
+                Err(err) if written > 0 => {
+                    self.error = Some(err);
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+            written += 1;
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn small_bytes_are_zero_padded() {
+        assert_eq!(encode(&[0x0b, 0x36, 0x00, 0xff]), "0b3600ff");
+        assert_eq!(encode_upper(&[0x0b, 0x36, 0x00, 0xff]), "0B3600FF");
+        assert_eq!(encode(&[]), "");
+    }
+
+    #[test]
+    fn decode_any_case() {
+        assert_eq!(decode("0b3600ff"), Ok(vec![0x0b, 0x36, 0x00, 0xff]));
+        assert_eq!(decode("0B3600Ff"), Ok(vec![0x0b, 0x36, 0x00, 0xff]));
+    }
+
+    #[test]
+    fn decode_strict_reports_position() {
+        assert_eq!(decode("0b36 00ff"), Err(Error::InvalidHex { position: 4 }));
+        assert_eq!(decode("0x36"), Err(Error::InvalidHex { position: 1 }));
+        assert_eq!(decode("0b360"), Err(Error::InvalidHex { position: 5 }));
+    }
+
+    #[test]
+    fn decode_lenient_skips_whitespace() {
+        assert_eq!(
+            decode_lenient("0b 36\n00\r\n\tf f\n"),
+            Ok(vec![0x0b, 0x36, 0x00, 0xff])
+        );
+        assert_eq!(
+            decode_lenient("0b 36\nz0"),
+            Err(Error::InvalidHex { position: 6 })
+        );
+        assert_eq!(
+            decode_lenient("0b 36 0\n"),
+            Err(Error::InvalidHex { position: 8 })
+        );
+    }
+
+    #[test]
+    fn streaming_roundtrip() {
+        let data: Vec<u8> = (0..10_000).map(|i: u32| i.to_le_bytes()[0] ^ 7).collect();
+
+        let mut encoder = Encoder::new(Vec::new());
+        for chunk in data.chunks(333) {
+            encoder.write_all(chunk).unwrap();
+        }
+        let hex = encoder.into_inner();
+        assert_eq!(hex, encode(&data).into_bytes());
+
+        let mut decoded = Vec::new();
+        Decoder::new(Cursor::new(hex))
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn streaming_decode_errors() {
+        let mut decoded = Vec::new();
+        let err = Decoder::new(Cursor::new("0b36\n00"))
+            .read_to_end(&mut decoded)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<Error>(),
+            Some(&Error::InvalidHex { position: 4 })
+        );
+
+        let mut decoded = Vec::new();
+        Decoder::lenient(Cursor::new("0b36\n00\n"))
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, vec![0x0b, 0x36, 0x00]);
+    }
+
+    #[test]
+    fn decoded_prefix_before_the_error() {
+        let mut decoder = Decoder::new(Cursor::new("0b36zz00"));
+        let mut buf = [0; 8];
+        assert_eq!(decoder.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf[..2], [0x0b, 0x36]);
+
+        let err = decoder.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<Error>(),
+            Some(&Error::InvalidHex { position: 4 })
+        );
+    }
+}
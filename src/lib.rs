@@ -2,10 +2,25 @@
 
 use std::iter;
 
-use itertools::Itertools;
+use log::{debug, trace};
 use rand::Rng;
 
+pub mod aes_cypher;
+pub mod attacks;
+pub mod classical;
+pub mod data;
+pub mod encoding;
+mod error;
 pub mod freq;
+pub mod hex;
+pub mod logging;
+pub mod oracle;
+pub mod plaintext;
+pub mod random;
+pub mod score;
+
+pub use self::error::{Error, Result};
+use self::{plaintext::PlaintextModel, score::Scorer};
 
 pub trait StreamCipher {
     fn xor<I>(&self, key: I) -> Vec<u8>
@@ -56,26 +71,22 @@ impl StreamCipher for [u8] {
 }
 
 pub trait StrCryptoExt {
-    fn parse_hex(&self) -> Vec<u8>;
+    /// # Errors
+    /// - non-hexadecimal symbol found
+    /// - odd number of hexadecimal digits
+    fn parse_hex(&self) -> Result<Vec<u8>>;
     fn is_printable_ascii(&self) -> bool;
-    fn strip_pkcs7_padding(&self, block_size: u8) -> Option<String>;
+
+    /// # Errors
+    /// - the string is not padded properly
+    /// - the unpadded bytes are not a valid UTF-8
+    fn strip_pkcs7_padding(&self, block_size: u8) -> Result<String>;
 }
 
 impl StrCryptoExt for str {
-    /// <https://codereview.stackexchange.com/a/201699>
-    fn parse_hex(&self) -> Vec<u8> {
-        let hex_bytes = self
-            .as_bytes()
-            .iter()
-            .filter_map(|b| match b {
-                b'0'..=b'9' => Some(b - b'0'),
-                b'a'..=b'f' => Some(b - b'a' + 10),
-                b'A'..=b'F' => Some(b - b'A' + 10),
-                _ => None,
-            })
-            .fuse();
-
-        hex_bytes.tuples().map(|(h, l)| h << 4 | l).collect()
+    /// Strict hexadecimal decoding, see the [`hex::decode`]
+    fn parse_hex(&self) -> Result<Vec<u8>> {
+        hex::decode(self)
     }
 
     fn is_printable_ascii(&self) -> bool {
@@ -83,86 +94,88 @@ impl StrCryptoExt for str {
             .all(|ch| ch.is_ascii_whitespace() || !ch.is_ascii_control())
     }
 
-    fn strip_pkcs7_padding(&self, block_size: u8) -> Option<String> {
+    fn strip_pkcs7_padding(&self, block_size: u8) -> Result<String> {
         let mut bytes: Vec<_> = self.bytes().collect();
-        let _padding_size = bytes.pkcs7_padding_size(block_size)?;
-
-        bytes.unpad_pkcs7(block_size);
-        String::from_utf8(bytes).ok()
+        bytes.unpad_pkcs7(block_size)?;
+        Ok(String::from_utf8(bytes)?)
     }
 }
 
-/// Higher score signifies the text is going further away from
-/// the standard english text (in terms of letter's frequencies
-fn english_text_score(text: &str) -> u64 {
-    #![allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_precision_loss,
-        clippy::cast_sign_loss
-    )]
-
-    let standard = freq::eng_map();
-    let real = freq::letters_frequencies(text);
-    let score: f64 = standard
-        .into_iter()
-        .map(|(ch, std_freq)| {
-            real.get(&ch).map_or(1.0, |freq| {
-                // the close to standard, the lower the score
-                (std_freq - freq).abs()
-            })
-        })
-        .sum();
-
-    let punctuation = text.chars().filter(char::is_ascii_punctuation).count();
-
-    // every punctuation sign adds a bit to the artificiality of the text
-    let punctuation_score = (punctuation as f64) * 0.2;
-    let score = score + punctuation_score;
-
-    (score * 1000.0) as u64
-}
-
 pub trait HexDisplay {
     fn as_hex(&self) -> String;
 }
 
 impl HexDisplay for [u8] {
+    /// Lowercase hexadecimal representation, see the [`hex::encode`]
     fn as_hex(&self) -> String {
-        self.iter().map(|x| format!("{:x}", x)).collect()
+        hex::encode(self)
     }
 }
 
 pub trait BytesCryptoExt {
-    fn guess_the_single_char_xor_key(&self) -> Vec<(u8, String, u64)>;
+    /// Try every single character key producing the valid UTF-8 text
+    /// and score it, the most English-like first.
+    fn guess_the_single_char_xor_key<S>(&self, scorer: &S) -> Vec<(u8, String, u64)>
+    where
+        S: Scorer + ?Sized;
 
     /// Find the most suitable single character (u8) that,
-    /// xor-ed to the given text produce a valid ASCII printable text
-    /// statistically closed to english text.
+    /// xor-ed to the given text produce a valid UTF-8 text
+    /// statistically closest to english text.
+    /// The unusual symbols are up to the scorer to penalize, see the [`score::ByteFrequency`].
     ///
     /// # Errors
-    /// - every character we try, produces bad string (not a valid UTF-8) when xor-ed
-    /// - every character we try, produces non-printable ASCII symbols
-    fn find_key_char(&self) -> Result<u8, String>;
+    /// Every character we try, produces bad string (not a valid UTF-8) when xor-ed
+    fn find_key_char<S>(&self, scorer: &S) -> Result<u8>
+    where
+        S: Scorer + ?Sized;
+
+    /// Try every single byte key and score the decrypted data with the model.
+    /// The candidates rejected by the model are skipped, the best ones go first.
+    fn guess_single_byte_xor_key<M>(&self, model: &M) -> Vec<(u8, Vec<u8>, u64)>
+    where
+        M: PlaintextModel + ?Sized;
+
+    /// The best single byte key according to the model
+    ///
+    /// # Errors
+    /// The model rejects the data decrypted with every key
+    fn find_key_byte<M>(&self, model: &M) -> Result<u8>
+    where
+        M: PlaintextModel + ?Sized;
 
     fn pad_pkcs7(&mut self, block_size: u8);
-    fn pkcs7_padding_size(&self, block_size: u8) -> Option<usize>;
-    fn unpad_pkcs7(&mut self, block_size: u8);
 
+    /// # Errors
+    /// The data does not end with the valid PKCS#7 padding
+    fn pkcs7_padding_size(&self, block_size: u8) -> Result<usize>;
+
+    /// # Errors
+    /// The data does not end with the valid PKCS#7 padding
+    fn unpad_pkcs7(&mut self, block_size: u8) -> Result<()>;
+
+    /// The random bytes from the [`random::rng`]
     fn generate_random(count: usize) -> Self;
+
+    fn generate_random_with<R>(count: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized;
 }
 
 impl BytesCryptoExt for Vec<u8> {
     #![allow(clippy::use_self)]
 
-    fn guess_the_single_char_xor_key(&self) -> Vec<(u8, String, u64)> {
-        // eprintln!("{:x?}", self);
-
+    fn guess_the_single_char_xor_key<S>(&self, scorer: &S) -> Vec<(u8, String, u64)>
+    where
+        S: Scorer + ?Sized,
+    {
+        #[allow(clippy::legacy_numeric_constants)]
         let keys_space = 0..=std::u8::MAX;
         let mut candidates: Vec<_> = keys_space
             .filter_map(|key| {
                 let raw = self.xor(iter::once(key));
                 String::from_utf8(raw).ok().map(|plain| {
-                    let score = english_text_score(&plain);
+                    let score = scorer.score(&plain);
                     (key, plain, score)
                 })
             })
@@ -170,48 +183,71 @@ impl BytesCryptoExt for Vec<u8> {
 
         candidates.sort_unstable_by_key(|(_key, _plain, score)| *score);
 
-        // print the top candidates for debug
         let debug_top_candidates = candidates.len().min(10);
         for (key, plain, score) in &candidates[..debug_top_candidates] {
-            eprintln!("{} ({}). {} -> {}", key, *key as char, plain, score);
+            trace!(target: logging::SCORING, "{} ({:?}). {:?} -> {}", key, *key as char, plain, score);
         }
 
         candidates
     }
 
     /// Find the most suitable single character (u8) that,
-    /// xor-ed to the given text produce a valid ASCII printable text
-    /// statistically closed to english text.
+    /// xor-ed to the given text produce a valid UTF-8 text
+    /// statistically closest to english text.
+    /// The unusual symbols are up to the scorer to penalize, see the [`score::ByteFrequency`].
     ///
     /// # Errors
-    /// - every character we try, produces bad string (not a valid UTF-8) when xor-ed
-    /// - every character we try, produces non-printable ASCII symbols
-    fn find_key_char(&self) -> Result<u8, String> {
-        let candidates = self.guess_the_single_char_xor_key();
-        if candidates.is_empty() {
-            return Err("No valid key char can be found".to_string());
-        }
-
-        eprintln!(
-            "Trying to decrypt the line {:?} with single character",
-            self
+    /// Every character we try, produces bad string (not a valid UTF-8) when xor-ed
+    fn find_key_char<S>(&self, scorer: &S) -> Result<u8>
+    where
+        S: Scorer + ?Sized,
+    {
+        debug!(
+            target: logging::ATTACK,
+            "Trying to decrypt the line {:?} with single character", self
         );
 
-        for (key, plain, score) in &candidates {
-            // unprintable non-whitespace symbol
-            if !plain.is_printable_ascii() {
-                continue;
-            }
+        let (key, plain, score) = self
+            .guess_the_single_char_xor_key(scorer)
+            .into_iter()
+            .next()
+            .ok_or(Error::NoCandidate)?;
+        debug!(
+            target: logging::ATTACK,
+            "The key is {}({:?}). Plaintext is: {:?} (score={})",
+            key,
+            key as char,
+            plain,
+            score
+        );
+        Ok(key)
+    }
 
-            eprintln!(
-                "The key is {}({:?}). Plaintext is: {:?} (score={})",
-                key, *key as char, plain, score
-            );
+    fn guess_single_byte_xor_key<M>(&self, model: &M) -> Vec<(u8, Vec<u8>, u64)>
+    where
+        M: PlaintextModel + ?Sized,
+    {
+        let keys_space = 0..=u8::MAX;
+        let mut candidates: Vec<_> = keys_space
+            .filter_map(|key| {
+                let plain = self.xor(iter::once(key));
+                let score = model.score(&plain)?;
+                Some((key, plain, score))
+            })
+            .collect();
 
-            return Ok(*key);
-        }
+        candidates.sort_by_key(|(_key, _plain, score)| *score);
+        candidates
+    }
 
-        Err("All the sequences has bad characters".to_string())
+    fn find_key_byte<M>(&self, model: &M) -> Result<u8>
+    where
+        M: PlaintextModel + ?Sized,
+    {
+        self.guess_single_byte_xor_key(model)
+            .first()
+            .map(|(key, _plain, _score)| *key)
+            .ok_or(Error::NoCandidate)
     }
 
     fn pad_pkcs7(&mut self, block_size: u8) {
@@ -227,141 +263,54 @@ impl BytesCryptoExt for Vec<u8> {
         self.extend(padding);
     }
 
-    fn pkcs7_padding_size(&self, block_size: u8) -> Option<usize> {
-        if self.is_empty() {
-            return None;
-        }
-
-        if block_size < 1 {
-            return None;
-        }
-
-        let last_byte = *self.last().unwrap();
-        if last_byte > block_size {
-            return None;
-        }
-
-        let unpad_bytes = last_byte as usize;
-        let padding: Vec<_> = self.iter().rev().take(unpad_bytes).collect();
-        if padding == vec![&last_byte; unpad_bytes] {
-            return Some(unpad_bytes);
-        }
-
-        None
+    fn pkcs7_padding_size(&self, block_size: u8) -> Result<usize> {
+        pkcs7_padding_size(self, block_size)
     }
 
-    fn unpad_pkcs7(&mut self, block_size: u8) {
-        if let Some(unpad_bytes) = self.pkcs7_padding_size(block_size) {
-            self.truncate(self.len() - unpad_bytes);
-        }
+    fn unpad_pkcs7(&mut self, block_size: u8) -> Result<()> {
+        let unpad_bytes = self.pkcs7_padding_size(block_size)?;
+        self.truncate(self.len() - unpad_bytes);
+        Ok(())
     }
 
     fn generate_random(count: usize) -> Self {
-        let mut rng = rand::thread_rng();
-        (0..count).map(|_| rng.gen()).collect()
+        Self::generate_random_with(count, &mut random::rng())
     }
-}
 
-pub fn hamming(lhs: impl AsRef<[u8]>, rhs: impl AsRef<[u8]>) -> u32 {
-    lhs.as_ref()
-        .iter()
-        .zip(rhs.as_ref())
-        .map(|(l, r)| (l ^ r).count_ones())
-        .sum()
+    fn generate_random_with<R>(count: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        (0..count).map(|_| rng.gen()).collect()
+    }
 }
 
-pub mod aes_cypher {
-    use aes::{cipher::generic_array::GenericArray, Aes128, BlockCipher, NewBlockCipher};
-
-    use super::{BytesCryptoExt, StreamCipher};
-
-    pub fn encrypt(mut data: Vec<u8>, key: &[u8]) -> Vec<Vec<u8>> {
-        let key = GenericArray::from_slice(key);
-        let cipher = Aes128::new(key);
-
-        data.pad_pkcs7(16);
-        data.chunks(16)
-            .map(move |block| {
-                let mut block = GenericArray::clone_from_slice(block);
-                cipher.encrypt_block(&mut block);
-                block.to_vec()
-            })
-            .collect()
+/// See the [`BytesCryptoExt::pkcs7_padding_size`]
+pub(crate) fn pkcs7_padding_size(data: &[u8], block_size: u8) -> Result<usize> {
+    if block_size < 1 {
+        return Err(Error::InvalidPadding);
     }
 
-    pub fn decrypt<'a, 'b>(data: &'a [u8], key: &'b [u8]) -> impl Iterator<Item = Vec<u8>> + 'a {
-        let key = GenericArray::from_slice(key);
-        let cipher = Aes128::new(key);
-
-        data.chunks(16).map(move |block| {
-            let mut block = GenericArray::clone_from_slice(block);
-            cipher.decrypt_block(&mut block);
-            block.to_vec()
-        })
+    let last_byte = *data.last().ok_or(Error::InvalidPadding)?;
+    if last_byte == 0 || last_byte > block_size {
+        return Err(Error::InvalidPadding);
     }
 
-    pub fn encrypt_cbc(mut data: Vec<u8>, key: &[u8], iv: Vec<u8>) -> Vec<Vec<u8>> {
-        let key = GenericArray::from_slice(key);
-        let cipher = Aes128::new(key);
-
-        data.pad_pkcs7(16);
-        data.chunks(16)
-            .scan(iv, move |prev_block, block| {
-                // mix with the previous block
-                let block = block.xor_ref(prev_block.iter());
-                let mut block = GenericArray::clone_from_slice(&block);
-                cipher.encrypt_block(&mut block);
-                *prev_block = block.to_vec();
-                Some(prev_block.clone())
-            })
-            .collect()
+    let unpad_bytes = last_byte as usize;
+    let padding: Vec<_> = data.iter().rev().take(unpad_bytes).collect();
+    if padding == vec![&last_byte; unpad_bytes] {
+        return Ok(unpad_bytes);
     }
 
-    pub fn decrypt_cbc<'a, 'b>(
-        data: &'a [u8],
-        key: &'b [u8],
-        iv: Vec<u8>,
-    ) -> impl Iterator<Item = Vec<u8>> + 'a {
-        let key = GenericArray::from_slice(key);
-        let cipher = Aes128::new(key);
-
-        data.chunks(16).scan(iv, move |prev_block, block| {
-            let mut current_xor = block.to_vec();
-            std::mem::swap(&mut current_xor, prev_block);
-
-            let mut block = GenericArray::clone_from_slice(block);
-            cipher.decrypt_block(&mut block);
-
-            // mix with the previous block
-            Some(block.xor_ref(current_xor.iter()))
-        })
-    }
+    Err(Error::InvalidPadding)
 }
 
-pub fn detect_block_size<Enc>(f: Enc) -> (usize, usize)
-where
-    Enc: Fn(&[u8]) -> Vec<u8>,
-{
-    let empty_enc_size: usize = f(&[]).len();
-    eprintln!("Empty payload ciphertext size: {}", empty_enc_size);
-
-    for i in 1.. {
-        let payload: Vec<_> = iter::repeat(b'A').take(i).collect();
-        let enc_size: usize = f(&payload).len();
-        eprintln!(
-            "The size of the ciphertext of the payload {:?} is {}",
-            payload, enc_size,
-        );
-
-        let diff = enc_size - empty_enc_size;
-
-        if diff != 0 {
-            let suffix_size = empty_enc_size - i;
-            return (diff, suffix_size);
-        }
-    }
-
-    unreachable!("The block size will be revealed eventually")
+pub fn hamming(lhs: impl AsRef<[u8]>, rhs: impl AsRef<[u8]>) -> u32 {
+    lhs.as_ref()
+        .iter()
+        .zip(rhs.as_ref())
+        .map(|(l, r)| (l ^ r).count_ones())
+        .sum()
 }
 
 #[cfg(test)]
@@ -404,7 +353,7 @@ mod tests {
     fn empty_string_has_no_padding() {
         let v = vec![];
         for block_size in 0..=16 {
-            assert_eq!(v.pkcs7_padding_size(block_size), None);
+            assert_eq!(v.pkcs7_padding_size(block_size), Err(Error::InvalidPadding));
         }
     }
 
@@ -412,18 +361,26 @@ mod tests {
     fn no_valid_padding_for_zero_block_size() {
         for byte in 0..=16 {
             let v = vec![byte];
-            assert_eq!(v.pkcs7_padding_size(0), None);
+            assert_eq!(v.pkcs7_padding_size(0), Err(Error::InvalidPadding));
+        }
+    }
+
+    #[test]
+    fn zero_byte_is_not_a_padding() {
+        for block_size in 1..=16 {
+            let v = vec![1, 2, 0];
+            assert_eq!(v.pkcs7_padding_size(block_size), Err(Error::InvalidPadding));
         }
     }
 
     #[test]
     fn the_only_valid_padding_for_stream() {
         let v = vec![1];
-        assert_eq!(v.pkcs7_padding_size(1), Some(1));
+        assert_eq!(v.pkcs7_padding_size(1), Ok(1));
 
         for byte in 2..=16 {
             let v = vec![byte];
-            assert_eq!(v.pkcs7_padding_size(1), None);
+            assert_eq!(v.pkcs7_padding_size(1), Err(Error::InvalidPadding));
         }
     }
 
@@ -431,14 +388,14 @@ mod tests {
     fn not_enough_bytes_padding() {
         // https://cryptopals.com/sets/2/challenges/15
         let s = "ICE ICE BABY\u{05}\u{05}\u{05}\u{05}";
-        assert_eq!(s.strip_pkcs7_padding(8), None);
+        assert_eq!(s.strip_pkcs7_padding(8), Err(Error::InvalidPadding));
     }
 
     #[test]
     fn bad_bytes_padding() {
         // https://cryptopals.com/sets/2/challenges/15
         let s = "ICE ICE BABY\u{01}\u{02}\u{03}\u{04}";
-        assert_eq!(s.strip_pkcs7_padding(16), None);
+        assert_eq!(s.strip_pkcs7_padding(16), Err(Error::InvalidPadding));
     }
 
     #[test]
@@ -447,4 +404,28 @@ mod tests {
         let s = "ICE ICE BABY\u{05}\u{05}\u{05}\u{05}\u{05}";
         assert_eq!(s.strip_pkcs7_padding(8).unwrap(), "ICE ICE BABY");
     }
+
+    #[test]
+    fn unpad_keeps_data_with_bad_padding() {
+        let mut v = vec![5_u8, 32, 16, 0, 4, 2];
+        assert_eq!(v.unpad_pkcs7(6), Err(Error::InvalidPadding));
+        assert_eq!(v, vec![5, 32, 16, 0, 4, 2]);
+    }
+
+    #[test]
+    fn parse_hex_strict() {
+        assert_eq!("0b3637".parse_hex(), Ok(vec![0x0b, 0x36, 0x37]));
+        assert_eq!("0B3G37".parse_hex(), Err(Error::InvalidHex { position: 3 }));
+        assert_eq!("0b363".parse_hex(), Err(Error::InvalidHex { position: 5 }));
+    }
+
+    #[test]
+    fn no_key_char_when_every_candidate_is_not_utf8() {
+        // either the first or the second byte always has the high bit set
+        let v = vec![0x80_u8, 0x00];
+        assert_eq!(
+            v.find_key_char(&score::ByteFrequency::default()),
+            Err(Error::NoCandidate)
+        );
+    }
 }
@@ -0,0 +1,110 @@
+//! The targets of the log messages and the simple stderr logger for the binaries.
+//!
+//! The library only emits the messages through the [`log`] facade,
+//! so nothing is printed until the application installs some logger.
+
+use std::io::{self, Write};
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// Scoring the candidate plaintexts
+pub const SCORING: &str = "pals::scoring";
+/// The queries to the oracles
+pub const ORACLE: &str = "pals::oracle";
+/// The progress of the attacks
+pub const ATTACK: &str = "pals::attack";
+
+/// The level of the messages to show for the number of the `-v` flags:
+/// the warnings by default, then the info, the debug and the trace.
+pub const fn level(verbosity: usize) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Writes every message of the enabled level as a line to the stderr
+#[derive(Debug, Clone, Copy)]
+pub struct StderrLogger {
+    level: LevelFilter,
+}
+
+impl StderrLogger {
+    pub const fn new(level: LevelFilter) -> Self {
+        Self { level }
+    }
+
+    fn format(record: &Record<'_>) -> String {
+        format!(
+            "[{:<5} {}] {}",
+            record.level(),
+            record.target(),
+            record.args()
+        )
+    }
+}
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            // nowhere to report the failure of the stderr
+            let _ = writeln!(io::stderr(), "{}", Self::format(record));
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
+
+/// Install the [`StderrLogger`] for the whole process
+///
+/// # Errors
+/// Some logger is already installed
+pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+    log::set_boxed_logger(Box::new(StderrLogger::new(level)))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+
+    use super::*;
+
+    #[test]
+    fn verbosity_levels() {
+        assert_eq!(level(0), LevelFilter::Warn);
+        assert_eq!(level(2), LevelFilter::Debug);
+        assert_eq!(level(10), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn filter_and_format() {
+        let logger = StderrLogger::new(LevelFilter::Info);
+        let debug = Metadata::builder()
+            .level(Level::Debug)
+            .target(SCORING)
+            .build();
+        assert!(!logger.enabled(&debug));
+
+        let args = format_args!("Found the key {}", 42);
+        let record = Record::builder()
+            .level(Level::Info)
+            .target(ATTACK)
+            .args(args)
+            .build();
+        assert!(logger.enabled(record.metadata()));
+        assert_eq!(
+            StderrLogger::format(&record),
+            "[INFO  pals::attack] Found the key 42"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! The black boxes the attacks are allowed to talk to.
+//!
+//! An oracle answers a query made of raw bytes. The answer depends on its kind:
+//! - encryption and decryption oracles answer with the bytes (`Vec<u8>`);
+//! - padding-validity and other yes/no oracles answer with the `bool`.
+//!
+//! Any `Fn(&[u8]) -> Answer` closure is an oracle already. Wrap it into the [`Metered`]
+//! to count the queries, limit them or record the [`Transcript`] to [`Replay`] later.
+
+use std::cell::{Cell, RefCell};
+
+use log::trace;
+
+use super::{logging, Error, Result};
+
+pub trait Oracle {
+    type Answer;
+
+    /// # Errors
+    /// The oracle refuses to answer
+    fn query(&self, input: &[u8]) -> Result<Self::Answer>;
+}
+
+impl<F, A> Oracle for F
+where
+    F: Fn(&[u8]) -> A,
+{
+    type Answer = A;
+
+    fn query(&self, input: &[u8]) -> Result<A> {
+        Ok(self(input))
+    }
+}
+
+/// All the queries made to the oracle along with its answers, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript<A> {
+    entries: Vec<(Vec<u8>, A)>,
+}
+
+impl<A> Default for Transcript<A> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<A> Transcript<A> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &A)> {
+        self.entries
+            .iter()
+            .map(|(query, answer)| (query.as_slice(), answer))
+    }
+
+    pub fn push(&mut self, query: &[u8], answer: A) {
+        self.entries.push((query.to_vec(), answer));
+    }
+
+    /// The oracle answering the same queries in the same order
+    pub const fn replay(self) -> Replay<A> {
+        Replay {
+            transcript: self,
+            next: Cell::new(0),
+        }
+    }
+}
+
+/// Counts the queries to the inner oracle
+/// and optionally limits their number or records them.
+pub struct Metered<O: Oracle> {
+    inner: O,
+    queries: Cell<usize>,
+    budget: Option<usize>,
+    transcript: Option<RefCell<Transcript<O::Answer>>>,
+}
+
+impl<O: Oracle> Metered<O> {
+    pub const fn new(inner: O) -> Self {
+        Self {
+            inner,
+            queries: Cell::new(0),
+            budget: None,
+            transcript: None,
+        }
+    }
+
+    /// Refuse to answer more than `max_queries`
+    #[must_use]
+    pub fn budget(self, max_queries: usize) -> Self {
+        Self {
+            budget: Some(max_queries),
+            ..self
+        }
+    }
+
+    /// Keep all the queries and answers
+    #[must_use]
+    pub fn recording(self) -> Self {
+        Self {
+            transcript: Some(RefCell::default()),
+            ..self
+        }
+    }
+
+    pub const fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    /// The number of answered queries
+    pub fn queries(&self) -> usize {
+        self.queries.get()
+    }
+
+    /// The recorded transcript (empty if the recording is not enabled)
+    pub fn into_transcript(self) -> Transcript<O::Answer> {
+        self.transcript.map(RefCell::into_inner).unwrap_or_default()
+    }
+}
+
+impl<O> Oracle for Metered<O>
+where
+    O: Oracle,
+    O::Answer: Clone,
+{
+    type Answer = O::Answer;
+
+    fn query(&self, input: &[u8]) -> Result<Self::Answer> {
+        if let Some(budget) = self.budget {
+            if self.queries.get() >= budget {
+                return Err(Error::QueryBudgetExceeded(budget));
+            }
+        }
+
+        let answer = self.inner.query(input)?;
+        self.queries.set(self.queries.get() + 1);
+        trace!(
+            target: logging::ORACLE,
+            "Query #{} of {} bytes",
+            self.queries.get(),
+            input.len()
+        );
+        if let Some(transcript) = &self.transcript {
+            transcript.borrow_mut().push(input, answer.clone());
+        }
+
+        Ok(answer)
+    }
+}
+
+/// Answers the queries from the recorded transcript.
+///
+/// The queries should come in exactly the same order as they were recorded.
+pub struct Replay<A> {
+    transcript: Transcript<A>,
+    next: Cell<usize>,
+}
+
+impl<A: Clone> Oracle for Replay<A> {
+    type Answer = A;
+
+    fn query(&self, input: &[u8]) -> Result<A> {
+        let index = self.next.get();
+        match self.transcript.entries.get(index) {
+            Some((query, answer)) if query.as_slice() == input => {
+                self.next.set(index + 1);
+                Ok(answer.clone())
+            }
+            _ => Err(Error::ReplayMismatch { query: index }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reverse(input: &[u8]) -> Vec<u8> {
+        input.iter().rev().copied().collect()
+    }
+
+    #[test]
+    fn closures_are_oracles() {
+        assert_eq!(reverse.query(&[1, 2, 3]), Ok(vec![3, 2, 1]));
+
+        let is_even = |input: &[u8]| input.len() % 2 == 0;
+        assert_eq!(is_even.query(&[1, 2]), Ok(true));
+    }
+
+    #[test]
+    fn count_the_queries() {
+        let oracle = Metered::new(reverse);
+        for i in 0..10 {
+            oracle.query(&[i]).unwrap();
+        }
+        assert_eq!(oracle.queries(), 10);
+        assert!(oracle.into_transcript().is_empty());
+    }
+
+    #[test]
+    fn exceeding_the_budget() {
+        let oracle = Metered::new(reverse).budget(2);
+        assert!(oracle.query(&[1]).is_ok());
+        assert!(oracle.query(&[2]).is_ok());
+        assert_eq!(oracle.query(&[3]), Err(Error::QueryBudgetExceeded(2)));
+        assert_eq!(oracle.queries(), 2);
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let oracle = Metered::new(reverse).recording();
+        oracle.query(&[1, 2]).unwrap();
+        oracle.query(&[3, 4, 5]).unwrap();
+
+        let transcript = oracle.into_transcript();
+        assert_eq!(
+            transcript.iter().collect::<Vec<_>>(),
+            vec![(&[1, 2][..], &vec![2, 1]), (&[3, 4, 5][..], &vec![5, 4, 3])]
+        );
+
+        let replay = transcript.replay();
+        assert_eq!(replay.query(&[1, 2]), Ok(vec![2, 1]));
+        assert_eq!(replay.query(&[3, 4, 5]), Ok(vec![5, 4, 3]));
+        assert_eq!(replay.query(&[6]), Err(Error::ReplayMismatch { query: 2 }));
+    }
+
+    #[test]
+    fn replay_out_of_order() {
+        let mut transcript = Transcript::default();
+        transcript.push(&[1], true);
+        transcript.push(&[2], false);
+
+        let replay = transcript.replay();
+        assert_eq!(replay.query(&[2]), Err(Error::ReplayMismatch { query: 0 }));
+        assert_eq!(replay.query(&[1]), Ok(true));
+    }
+}
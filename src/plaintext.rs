@@ -0,0 +1,299 @@
+//! The models of the expected plaintext to tell the right key from the wrong ones.
+//!
+//! The text models reject the candidates with the bad symbols
+//! and prefer the English letters frequencies.
+//! The binary models help to break the files that are not a text at all.
+
+use crate::{
+    freq::{ByteProfile, Language},
+    score::{LetterFrequency, Scorer},
+};
+
+pub trait PlaintextModel {
+    /// How far the candidate is from the expected plaintext, the lower the better.
+    /// `None` if the candidate cannot be such a plaintext at all.
+    fn score(&self, candidate: &[u8]) -> Option<u64>;
+}
+
+/// The printable ASCII text (whitespaces allowed) in English
+#[derive(Debug, Clone, Copy)]
+pub struct AsciiText<S = LetterFrequency> {
+    scorer: S,
+}
+
+impl<S: Scorer> AsciiText<S> {
+    pub const fn new(scorer: S) -> Self {
+        Self { scorer }
+    }
+}
+
+impl Default for AsciiText {
+    fn default() -> Self {
+        Self::new(LetterFrequency)
+    }
+}
+
+impl<S: Scorer> PlaintextModel for AsciiText<S> {
+    fn score(&self, candidate: &[u8]) -> Option<u64> {
+        let printable = candidate
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace());
+        if !printable {
+            return None;
+        }
+
+        let text = std::str::from_utf8(candidate).ok()?;
+        Some(self.scorer.score(text))
+    }
+}
+
+/// The valid UTF-8 text without the control characters (whitespaces allowed)
+/// scored as the English one
+#[derive(Debug, Clone, Copy)]
+pub struct Utf8Text<S = LetterFrequency> {
+    scorer: S,
+}
+
+impl<S: Scorer> Utf8Text<S> {
+    pub const fn new(scorer: S) -> Self {
+        Self { scorer }
+    }
+}
+
+impl Default for Utf8Text {
+    fn default() -> Self {
+        Self::new(LetterFrequency)
+    }
+}
+
+impl<S: Scorer> PlaintextModel for Utf8Text<S> {
+    fn score(&self, candidate: &[u8]) -> Option<u64> {
+        let text = std::str::from_utf8(candidate).ok()?;
+        if text
+            .chars()
+            .any(|ch| ch.is_control() && !ch.is_whitespace())
+        {
+            return None;
+        }
+
+        Some(self.scorer.score(text))
+    }
+}
+
+/// The cross-entropy (in millibits per byte) between the candidate
+/// and the expected distribution of the bytes.
+///
+/// The entropy of the candidate itself is useless here:
+/// XOR-ing with a single byte just swaps the bytes values keeping their frequencies.
+#[derive(Debug, Clone)]
+pub struct ByteEntropy {
+    profile: ByteProfile,
+}
+
+/// The share of zero bytes in the [`ByteEntropy::default`] model
+const ZERO_BYTE_SHARE: f64 = 0.25;
+
+impl Default for ByteEntropy {
+    /// The executables and most of the other binary formats
+    /// are full of zeroes (paddings, small integers, offsets)
+    fn default() -> Self {
+        let mut weights = [(1.0 - ZERO_BYTE_SHARE) / 255.0; 256];
+        weights[0] = ZERO_BYTE_SHARE;
+        Self::new(ByteProfile::from_weights(weights))
+    }
+}
+
+impl ByteEntropy {
+    pub const fn new(profile: ByteProfile) -> Self {
+        Self { profile }
+    }
+
+    /// Learn the distribution of the bytes from the sample of the expected plaintext.
+    /// The bytes never seen in the sample still get a tiny chance.
+    pub fn from_sample(sample: &[u8]) -> Self {
+        Self::new(ByteProfile::train(sample))
+    }
+
+    /// The text in any language, with no need to reject the candidates
+    /// with the unusual symbols: they just make the score worse.
+    pub fn text(language: Language) -> Self {
+        Self::new(language.byte_profile())
+    }
+}
+
+impl PlaintextModel for ByteEntropy {
+    fn score(&self, candidate: &[u8]) -> Option<u64> {
+        #![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+        Some((self.profile.cross_entropy(candidate) * 1000.0) as u64)
+    }
+}
+
+/// The well known file formats recognized by their first bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Magic {
+    Elf,
+    Png,
+    Zip,
+    Pdf,
+}
+
+impl Magic {
+    pub const ALL: [Self; 4] = [Self::Elf, Self::Png, Self::Zip, Self::Pdf];
+
+    pub const fn signature(self) -> &'static [u8] {
+        match self {
+            Self::Elf => b"\x7fELF",
+            Self::Png => b"\x89PNG\r\n\x1a\n",
+            Self::Zip => b"PK\x03\x04",
+            Self::Pdf => b"%PDF-",
+        }
+    }
+
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|magic| data.starts_with(magic.signature()))
+    }
+}
+
+/// The file starting with one of the known signatures.
+/// Every candidate matching the signature is equally good.
+#[derive(Debug, Clone)]
+pub struct FileMagic {
+    formats: Vec<Magic>,
+}
+
+impl Default for FileMagic {
+    /// Any of the known formats
+    fn default() -> Self {
+        Self::new(Magic::ALL.to_vec())
+    }
+}
+
+impl FileMagic {
+    pub const fn new(formats: Vec<Magic>) -> Self {
+        Self { formats }
+    }
+}
+
+impl PlaintextModel for FileMagic {
+    fn score(&self, candidate: &[u8]) -> Option<u64> {
+        let magic = Magic::detect(candidate)?;
+        if self.formats.contains(&magic) {
+            Some(0)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{score::ChiSquared, BytesCryptoExt, Error, StreamCipher};
+
+    use super::*;
+
+    #[test]
+    fn ascii_text_rejects_non_ascii() {
+        assert!(AsciiText::default()
+            .score(b"Cooking MC's like a pound of bacon\n")
+            .is_some());
+        assert!(AsciiText::default().score("Caf\u{e9}".as_bytes()).is_none());
+        assert!(AsciiText::default().score(b"bell\x07").is_none());
+    }
+
+    #[test]
+    fn utf8_text_accepts_non_ascii() {
+        assert!(Utf8Text::default()
+            .score("Caf\u{e9} au lait".as_bytes())
+            .is_some());
+        assert!(Utf8Text::default().score(b"\xff\xfe").is_none());
+        assert!(Utf8Text::default().score(b"bell\x07").is_none());
+    }
+
+    #[test]
+    fn byte_entropy_from_sample() {
+        let model = ByteEntropy::from_sample(b"aaaaaaaab");
+        let common = model.score(b"aaaa").unwrap();
+        let rare = model.score(b"zzzz").unwrap();
+        assert!(common < rare);
+        assert_eq!(model.score(b""), Some(0));
+    }
+
+    #[test]
+    fn byte_entropy_of_the_text() {
+        let plain = "Cooking MC's like a pound of bacon";
+        let data = plain.xor(std::iter::once(b'X'));
+        let model = ByteEntropy::text(Language::English);
+
+        let candidates = data.guess_single_byte_xor_key(&model);
+        // no candidate is rejected, even the ones full of control symbols
+        assert_eq!(candidates.len(), 256);
+        assert_eq!(candidates[0].0, b'X');
+        // the case flipped text has the zero bytes instead of spaces
+        let (flipped, _, _) = candidates
+            .iter()
+            .find(|(_, plain, _)| plain.starts_with(b"cOOKING"))
+            .unwrap();
+        assert_eq!(*flipped, b'x');
+    }
+
+    #[test]
+    fn detect_magic() {
+        assert_eq!(Magic::detect(b"%PDF-1.7\n"), Some(Magic::Pdf));
+        assert_eq!(Magic::detect(b"PK\x03\x04\x14\x00"), Some(Magic::Zip));
+        assert_eq!(Magic::detect(b"PK"), None);
+
+        let png_only = FileMagic::new(vec![Magic::Png]);
+        assert_eq!(png_only.score(b"\x89PNG\r\n\x1a\n\x00"), Some(0));
+        assert_eq!(png_only.score(b"\x7fELF\x02\x01"), None);
+    }
+
+    #[test]
+    fn text_models_agree_with_the_text_key() {
+        let data = "Now that the party is jumping".xor(std::iter::once(b'X'));
+        assert_eq!(data.find_key_byte(&AsciiText::default()), Ok(b'X'));
+        assert_eq!(data.find_key_byte(&Utf8Text::new(ChiSquared)), Ok(b'X'));
+    }
+
+    #[cfg(target_os = "linux")]
+    fn encrypted_executable(key: u8) -> Vec<u8> {
+        let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        exe[..exe.len().min(1 << 16)].xor(std::iter::once(key))
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn break_the_executable_by_magic() {
+        let data = encrypted_executable(0x5a);
+        let key = data.find_key_byte(&FileMagic::default()).unwrap();
+        assert_eq!(key, 0x5a);
+        assert_eq!(
+            Magic::detect(&data.xor(std::iter::once(key))),
+            Some(Magic::Elf)
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn break_the_executable_by_entropy() {
+        let data = encrypted_executable(0xa5);
+        assert_eq!(data.find_key_byte(&ByteEntropy::default()), Ok(0xa5));
+
+        let candidates = data.guess_single_byte_xor_key(&ByteEntropy::default());
+        assert_eq!(candidates.len(), 256);
+        assert!(candidates.windows(2).all(|pair| pair[0].2 <= pair[1].2));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn the_executable_is_not_a_text() {
+        let data = encrypted_executable(0x5a);
+        assert_eq!(
+            data.find_key_byte(&AsciiText::default()),
+            Err(Error::NoCandidate)
+        );
+    }
+}
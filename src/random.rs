@@ -0,0 +1,186 @@
+//! The single source of the randomness for the keys, IVs and the black boxes.
+//!
+//! Every thread has its own generator seeded with the process-wide seed
+//! mixed with the number of the thread (in the order of their first use of the generator).
+//! The seed is random unless given in the `PALS_SEED` environment variable,
+//! so any run can be replayed exactly with the seed it has printed.
+
+use std::{
+    cell::RefCell,
+    env, panic,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use lazy_static::lazy_static;
+use log::info;
+use rand::{rngs::StdRng, Error as RandError, RngCore, SeedableRng};
+
+use crate::Error;
+
+/// The environment variable to override the [`seed`]
+pub const SEED_VAR: &str = "PALS_SEED";
+
+lazy_static! {
+    static ref SEED: crate::Result<u64> = {
+        // the failed tests show how to replay them
+        #[cfg(test)]
+        install_panic_hook();
+
+        env::var(SEED_VAR).map_or_else(
+            |_| {
+                let seed = rand::random();
+                info!("Generated the random seed: {}={}", SEED_VAR, seed);
+                Ok(seed)
+            },
+            parse_seed,
+        )
+    };
+}
+
+fn parse_seed(seed: String) -> crate::Result<u64> {
+    seed.trim().parse().map_err(|_| Error::InvalidSeed(seed))
+}
+
+/// How many threads have used the generator so far
+static THREADS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD_NUMBER: u64 = THREADS.fetch_add(1, Ordering::Relaxed);
+    static RNG: RefCell<StdRng> = RefCell::new(thread_rng(seed()));
+}
+
+/// The generator of the current thread derived from the process-wide seed
+fn thread_rng(seed: u64) -> StdRng {
+    let thread = THREAD_NUMBER.with(|number| *number);
+    StdRng::seed_from_u64(mix(seed ^ mix(thread)))
+}
+
+/// The `SplitMix64` finalizer, so the close seeds give unrelated streams
+const fn mix(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// The seed of the generators for the whole process.
+/// Read it first with the [`try_seed`] to report the invalid one.
+///
+/// # Panics
+/// The `PALS_SEED` is not a valid `u64`
+pub fn seed() -> u64 {
+    try_seed().unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// The seed of the generators for the whole process
+///
+/// # Errors
+/// The `PALS_SEED` is not a valid `u64`
+pub fn try_seed() -> crate::Result<u64> {
+    SEED.clone()
+}
+
+/// Restart the generator of the current thread with the seed,
+/// e.g. to replay some part of the program without the rest of it.
+/// The `reseed(seed())` starts the stream of the thread from the beginning.
+pub fn reseed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = thread_rng(seed));
+}
+
+/// The handle to the generator of the current thread
+pub const fn rng() -> SeededRng {
+    SeededRng
+}
+
+/// Delegates to the generator of the current thread, see the [`rng`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeededRng;
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), RandError> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+/// Print the seed along with the message of any panic,
+/// so the failed run can be replayed
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        eprintln!("{}", replay_hint());
+    }));
+}
+
+/// How to replay the run with the same randomness
+pub fn replay_hint() -> String {
+    try_seed().map_or_else(
+        |err| err.to_string(),
+        |seed| format!("To replay the run, set the {}={}", SEED_VAR, seed),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn reseed_replays_the_sequence() {
+        reseed(42);
+        let first: Vec<u8> = (0..32).map(|_| rng().gen()).collect();
+        let range: Vec<_> = (0..10).map(|_| rng().gen_range(100, 1000)).collect();
+
+        reseed(42);
+        let second: Vec<u8> = (0..32).map(|_| rng().gen()).collect();
+        assert_eq!(first, second);
+        let replayed: Vec<_> = (0..10).map(|_| rng().gen_range(100, 1000)).collect();
+        assert_eq!(range, replayed);
+
+        reseed(43);
+        let other: Vec<u8> = (0..32).map(|_| rng().gen()).collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn own_stream_in_every_thread() {
+        let generate = || {
+            let first = rng().next_u64();
+            reseed(seed());
+            assert_eq!(rng().next_u64(), first);
+            first
+        };
+        let mut streams: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(generate).join().unwrap())
+            .collect();
+        streams.sort_unstable();
+        streams.dedup();
+        assert_eq!(streams.len(), 4);
+    }
+
+    #[test]
+    fn invalid_seed() {
+        assert_eq!(parse_seed(" 42\n".to_string()), Ok(42));
+        assert_eq!(
+            parse_seed("-1".to_string()),
+            Err(Error::InvalidSeed("-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn hint_has_the_seed() {
+        assert!(replay_hint().ends_with(&format!("PALS_SEED={}", seed())));
+    }
+}
@@ -0,0 +1,422 @@
+//! Scoring how close the text is to the English one.
+//!
+//! The letters frequencies work fine on the long texts only.
+//! The n-grams and the dictionary tell the English from the gibberish
+//! on a few dozens of symbols, so prefer them for the short ciphertexts.
+
+use std::collections::{HashMap, HashSet};
+
+use lazy_static::lazy_static;
+
+use crate::freq::{self, ByteProfile, Language, NGramModel};
+
+pub trait Scorer {
+    /// Higher score signifies the text is going further away from
+    /// the standard English text
+    fn score(&self, text: &str) -> u64;
+}
+
+impl<S: Scorer + ?Sized> Scorer for &S {
+    fn score(&self, text: &str) -> u64 {
+        (*self).score(text)
+    }
+}
+
+/// The sum of the absolute differences between the letters frequencies
+/// and the standard ones. All the other symbols are ignored,
+/// see the [`ByteFrequency`] to take them into account.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LetterFrequency;
+
+impl Scorer for LetterFrequency {
+    fn score(&self, text: &str) -> u64 {
+        #![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+        let standard = freq::eng_map();
+        let real = freq::letters_frequencies(text);
+        let score: f64 = standard
+            .into_iter()
+            .map(|(ch, std_freq)| {
+                real.get(&ch).map_or(1.0, |freq| {
+                    // the close to standard, the lower the score
+                    (std_freq - freq).abs()
+                })
+            })
+            .sum();
+
+        (score * 1000.0) as u64
+    }
+}
+
+/// The cross-entropy (in millibits per byte) of the text bytes
+/// against the full byte profile of the language.
+///
+/// Every symbol counts, including the whitespaces, the punctuation
+/// and the letters case, while the unusual bytes get the high penalty.
+#[derive(Debug, Clone)]
+pub struct ByteFrequency {
+    profile: ByteProfile,
+}
+
+impl ByteFrequency {
+    pub const fn new(profile: ByteProfile) -> Self {
+        Self { profile }
+    }
+}
+
+impl Default for ByteFrequency {
+    fn default() -> Self {
+        Self::new(Language::English.byte_profile())
+    }
+}
+
+impl Scorer for ByteFrequency {
+    fn score(&self, text: &str) -> u64 {
+        #![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+        (self.profile.cross_entropy(text.as_bytes()) * 1000.0) as u64
+    }
+}
+
+/// The share of the letters in the English text
+const LETTERS_SHARE: f64 = 0.80;
+/// The share of the whitespaces in the English text
+const SPACES_SHARE: f64 = 0.17;
+/// The share of the digits, punctuation and everything else in the English text
+const OTHER_SHARE: f64 = 0.03;
+
+/// Pearson's chi-squared test of the symbols counts against the English ones.
+///
+/// Besides the 26 (case insensitive) letters, the whitespaces
+/// and all the other symbols are counted as two more categories.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChiSquared;
+
+impl Scorer for ChiSquared {
+    fn score(&self, text: &str) -> u64 {
+        #![allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+
+        let mut letters = [0_usize; 26];
+        let mut spaces = 0;
+        let mut other = 0;
+        let mut total = 0_usize;
+        for ch in text.chars() {
+            total += 1;
+            if ch.is_ascii_alphabetic() {
+                letters[usize::from(ch.to_ascii_uppercase() as u8 - b'A')] += 1;
+            } else if ch.is_whitespace() {
+                spaces += 1;
+            } else {
+                other += 1;
+            }
+        }
+
+        // nothing to compare with the English
+        if total == 0 {
+            return u64::MAX;
+        }
+
+        let total = total as f64;
+        let chi_squared = |observed: usize, share: f64| {
+            let expected = total * share;
+            let diff = observed as f64 - expected;
+            diff * diff / expected
+        };
+
+        let standard = freq::eng_map();
+        let letters_score: f64 = letters
+            .iter()
+            .zip('A'..='Z')
+            .map(|(&count, ch)| chi_squared(count, standard[&ch] * LETTERS_SHARE))
+            .sum();
+        let score =
+            letters_score + chi_squared(spaces, SPACES_SHARE) + chi_squared(other, OTHER_SHARE);
+
+        (score * 1000.0) as u64
+    }
+}
+
+/// Log-likelihood of the text made of the overlapping n-grams,
+/// see the [`freq::symbols`] for how the text is split into them.
+#[derive(Debug, Clone)]
+pub struct NGrams {
+    n: usize,
+    log_probabilities: HashMap<Vec<char>, f64>,
+    /// The log-probability of the n-gram never seen in the training text
+    floor: f64,
+}
+
+lazy_static! {
+    static ref ENGLISH_BIGRAMS: NGrams = NGrams::new(&Language::English.model(2));
+    static ref ENGLISH_QUADGRAMS: NGrams = NGrams::new(&Language::English.model(4));
+    static ref ENGLISH_DICTIONARY: Dictionary = Dictionary::train(Language::English.corpus());
+}
+
+impl NGrams {
+    pub fn new(model: &NGramModel) -> Self {
+        #![allow(clippy::cast_precision_loss)]
+
+        let total = model.total().max(1) as f64;
+        let log_probabilities = model
+            .most_frequent()
+            .into_iter()
+            .map(|(gram, count)| (gram.to_vec(), (count as f64 / total).log10()))
+            .collect();
+
+        Self {
+            n: model.n(),
+            log_probabilities,
+            floor: (0.01 / total).log10(),
+        }
+    }
+
+    /// Count the n-grams of the training text
+    ///
+    /// # Panics
+    /// The `n` is zero
+    pub fn train(n: usize, text: &str) -> Self {
+        Self::new(&NGramModel::train(n, text))
+    }
+
+    /// Trained on the built-in English texts
+    pub fn english_bigrams() -> &'static Self {
+        &ENGLISH_BIGRAMS
+    }
+
+    /// Trained on the built-in English texts
+    pub fn english_quadgrams() -> &'static Self {
+        &ENGLISH_QUADGRAMS
+    }
+
+    pub const fn n(&self) -> usize {
+        self.n
+    }
+}
+
+impl Scorer for NGrams {
+    /// The average negative log-likelihood of the n-grams (in thousandths)
+    fn score(&self, text: &str) -> u64 {
+        #![allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+
+        let symbols = freq::symbols(text);
+        let grams = symbols.windows(self.n);
+        let count = grams.len();
+        let log_likelihood = if count == 0 {
+            // too short to have any n-gram
+            self.floor
+        } else {
+            grams
+                .map(|gram| {
+                    self.log_probabilities
+                        .get(gram)
+                        .copied()
+                        .unwrap_or(self.floor)
+                })
+                .sum::<f64>()
+                / count as f64
+        };
+
+        (-log_likelihood * 1000.0) as u64
+    }
+}
+
+/// The share of the words not found in the dictionary (in thousandths)
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    pub fn new<I, W>(words: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().to_lowercase())
+            .collect();
+        Self { words }
+    }
+
+    /// Collect all the words of the training text
+    pub fn train(text: &str) -> Self {
+        Self::new(split_words(text))
+    }
+
+    /// Trained on the built-in English texts
+    pub fn english() -> &'static Self {
+        &ENGLISH_DICTIONARY
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| !ch.is_alphabetic())
+        .filter(|word| !word.is_empty())
+}
+
+impl Scorer for Dictionary {
+    fn score(&self, text: &str) -> u64 {
+        let mut total = 0;
+        let mut hits = 0;
+        for word in split_words(text) {
+            total += 1;
+            if self.contains(word) {
+                hits += 1;
+            }
+        }
+
+        if total == 0 {
+            return 1000;
+        }
+        (total - hits) * 1000 / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use crate::{plaintext::AsciiText, BytesCryptoExt, StreamCipher};
+
+    use super::*;
+
+    const ENGLISH: &str = "Now that the party is jumping";
+    const GIBBERISH: &str = "Xqz vkjw pfl ttnqa ow zzgrmbx";
+
+    fn scorers() -> Vec<Box<dyn Scorer>> {
+        vec![
+            Box::new(ByteFrequency::default()),
+            Box::new(ChiSquared),
+            Box::new(NGrams::english_bigrams()),
+            Box::new(NGrams::english_quadgrams()),
+            Box::new(Dictionary::english()),
+        ]
+    }
+
+    #[test]
+    fn english_is_better_than_gibberish() {
+        for (i, scorer) in scorers().iter().enumerate() {
+            assert!(
+                scorer.score(ENGLISH) < scorer.score(GIBBERISH),
+                "{}: {} vs {}",
+                i,
+                scorer.score(ENGLISH),
+                scorer.score(GIBBERISH)
+            );
+        }
+    }
+
+    #[test]
+    fn letter_frequency_fails_on_short_text() {
+        assert!(LetterFrequency.score(ENGLISH) > LetterFrequency.score(GIBBERISH));
+    }
+
+    #[test]
+    fn byte_frequency_tells_the_spaces() {
+        let text = "Cooking MC's like a pound of bacon";
+        let scorer = ByteFrequency::default();
+        for other in &["\t", "_", "\u{7f}", "\u{e9}"] {
+            let swapped = text.replace(' ', other);
+            assert!(scorer.score(text) < scorer.score(&swapped), "{:?}", other);
+        }
+        // the letters frequencies don't see the difference
+        let swapped = text.replace(' ', "\t");
+        let diff = LetterFrequency
+            .score(text)
+            .abs_diff(LetterFrequency.score(&swapped));
+        // up to the rounding of the floating sums
+        assert!(diff <= 1);
+    }
+
+    #[test]
+    fn statistical_scorers_find_the_key() {
+        let data = "Cooking MC's like a pound of bacon".xor(iter::once(b'X'));
+        let mut scorers = scorers();
+        // the dictionary only tells apart the readable texts:
+        // the garbage with a single short word in it is as good as the English
+        scorers.pop();
+        for scorer in scorers {
+            assert_eq!(data.find_key_char(scorer.as_ref()), Ok(b'X'));
+        }
+    }
+
+    #[test]
+    fn printable_filter_helps_the_letters_and_the_dictionary() {
+        let data = "Cooking MC's like a pound of bacon".xor(iter::once(b'X'));
+        assert_eq!(
+            data.find_key_byte(&AsciiText::new(LetterFrequency)),
+            Ok(b'X')
+        );
+        assert_eq!(
+            data.find_key_byte(&AsciiText::new(Dictionary::english())),
+            Ok(b'X')
+        );
+    }
+
+    #[test]
+    fn quadgrams_break_the_short_text() {
+        for (plain, key) in &[
+            ("Ice Ice Baby", b'5'),
+            ("go to the sea", b'!'),
+            ("the end", b'E'),
+        ] {
+            let data = plain.xor(iter::once(*key));
+            assert_eq!(
+                data.find_key_char(NGrams::english_quadgrams()),
+                Ok(*key),
+                "{}",
+                plain
+            );
+        }
+    }
+
+    #[test]
+    fn ngrams_normalize_the_text() {
+        let quadgrams = NGrams::english_quadgrams();
+        assert_eq!(quadgrams.n(), 4);
+        assert_eq!(
+            quadgrams.score("Of the people"),
+            quadgrams.score("OF  THE\nPEOPLE")
+        );
+        assert!(quadgrams.score("of the people") < quadgrams.score("of the peop!e"));
+        // too short for any quadgram
+        assert_eq!(quadgrams.score("the"), quadgrams.score(""));
+    }
+
+    #[test]
+    fn ngrams_of_other_languages() {
+        let russian = NGrams::new(&Language::Russian.model(4));
+        let german = NGrams::new(&Language::German.model(4));
+        let text = "все люди рождаются свободными";
+        assert!(russian.score(text) < german.score(text));
+        assert!(russian.score(text) < russian.score("Alle Menschen sind frei"));
+    }
+
+    #[test]
+    fn chi_squared_of_nothing() {
+        assert_eq!(ChiSquared.score(""), u64::MAX);
+        assert!(ChiSquared.score(ENGLISH) < ChiSquared.score(""));
+    }
+
+    #[test]
+    fn dictionary_hit_rate() {
+        let dictionary = Dictionary::new(["ice", "baby"]);
+        assert!(dictionary.contains("ICE"));
+        assert_eq!(dictionary.score("Ice Ice Baby"), 0);
+        assert_eq!(dictionary.score("Ice Ice Baby, too cold"), 400);
+        assert_eq!(dictionary.score("42"), 1000);
+    }
+}